@@ -1,13 +1,138 @@
+use std::fmt;
+
 use models::direction::Direction;
+use models::owner::Owner;
 
-#[derive(Clone, Debug, Copy)]
-pub enum ActionType {
-    Move,
+/// What an action does: the name its handler is registered under.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ActionKind(pub &'static str);
+
+impl ActionKind {
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl fmt::Display for ActionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Most numbers a `Payload::Values` holds.
+pub const MAX_VALUES: usize = 4;
+
+/// Numbers an action added by a mod carries, which its handler makes sense
+/// of: a trade's partner and amount, say.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Values {
+    len: usize,
+    items: [i32; MAX_VALUES],
+}
+
+impl Values {
+    /// `None` if there are more than `MAX_VALUES` of them.
+    pub fn new(values: &[i32]) -> Option<Values> {
+        if values.len() > MAX_VALUES {
+            return None;
+        }
+
+        let mut items = [0; MAX_VALUES];
+        items[..values.len()].copy_from_slice(values);
+        Some(Values {
+            len: values.len(),
+            items,
+        })
+    }
+
+    pub fn as_slice(&self) -> &[i32] {
+        &self.items[..self.len]
+    }
+}
+
+/// What an action is aimed at. Each kind of action expects one shape of
+/// payload; handlers refuse the others.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum Payload {
+    None,
+    Direction(Direction),
+    Target(i32),
+    /// Anything else, for handlers to parse themselves.
+    Values(Values),
+}
+
+impl Payload {
+    pub fn direction(&self) -> Option<Direction> {
+        match self {
+            Payload::Direction(dir) => Some(*dir),
+            _ => None,
+        }
+    }
+
+    pub fn target(&self) -> Option<i32> {
+        match self {
+            Payload::Target(id) => Some(*id),
+            _ => None,
+        }
+    }
+
+    pub fn values(&self) -> Option<&[i32]> {
+        match self {
+            Payload::Values(values) => Some(values.as_slice()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Copy)]
 pub struct ActionData {
     pub entity_id: i32,
-    pub action_type: ActionType,
-    pub direction: Option<Direction>,
+    pub kind: ActionKind,
+    pub payload: Payload,
+    pub issuer: Owner,
+}
+
+impl ActionData {
+    /// An action of `entity_id`, issued by the engine.
+    pub fn new(entity_id: i32, kind: ActionKind, payload: Payload) -> ActionData {
+        ActionData {
+            entity_id,
+            kind,
+            payload,
+            issuer: Owner::Engine,
+        }
+    }
+
+    pub fn with_issuer(&self, issuer: Owner) -> ActionData {
+        ActionData { issuer, ..*self }
+    }
+
+    pub fn direction(&self) -> Option<Direction> {
+        self.payload.direction()
+    }
+
+    pub fn target(&self) -> Option<i32> {
+        self.payload.target()
+    }
+
+    pub fn values(&self) -> Option<&[i32]> {
+        self.payload.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values() {
+        let values = Values::new(&[2, 10]).unwrap();
+        assert_eq!(values.as_slice(), &[2, 10]);
+        assert_eq!(Values::new(&[]).unwrap().as_slice(), &[] as &[i32]);
+        assert!(Values::new(&[1; MAX_VALUES + 1]).is_none());
+
+        let trade = ActionData::new(1, ActionKind("trade"), Payload::Values(values));
+        assert_eq!(trade.values(), Some(&[2, 10][..]));
+        assert!(trade.target().is_none());
+    }
 }
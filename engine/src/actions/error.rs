@@ -6,6 +6,19 @@ pub enum ActionError {
     InvalidEntityId(i32),
     OutOfMapCoordinate(f32, f32),
     PositionOccupied(f32, f32),
+    NotOwner(i32),
+    NoTarget,
+    InvalidTarget(i32),
+    OutOfRange(i32),
+    NoLineOfSight(i32),
+    OutOfAmmo(i32),
+    InvalidDirection(f32, f32),
+    CutsCorner(f32, f32),
+    Towed(i32),
+    TooHeavy(f32, f32),
+    Forbidden(i32),
+    UnknownAction(&'static str),
+    GameOver,
 }
 
 impl fmt::Display for ActionError {
@@ -16,6 +29,23 @@ impl fmt::Display for ActionError {
                 write!(f, "Coordinate is outside world: {},{}", x, y)
             }
             ActionError::PositionOccupied(x, y) => write!(f, "Coordinate is occupied: {},{}", x, y),
+            ActionError::NotOwner(id) => write!(f, "Not allowed to command entity: {}", id),
+            ActionError::NoTarget => write!(f, "Nothing to shoot at"),
+            ActionError::InvalidTarget(id) => write!(f, "Can't shoot at entity: {}", id),
+            ActionError::OutOfRange(id) => write!(f, "Target is out of range: {}", id),
+            ActionError::NoLineOfSight(id) => write!(f, "Target is not in sight: {}", id),
+            ActionError::OutOfAmmo(id) => write!(f, "Out of ammo: {}", id),
+            ActionError::InvalidDirection(dx, dy) => {
+                write!(f, "Direction is not allowed: {},{}", dx, dy)
+            }
+            ActionError::CutsCorner(x, y) => write!(f, "Can't cut the corner to: {},{}", x, y),
+            ActionError::Towed(id) => write!(f, "Entity only moves with its leader: {}", id),
+            ActionError::TooHeavy(x, y) => write!(f, "Too much to push at: {},{}", x, y),
+            ActionError::Forbidden(id) => {
+                write!(f, "Action is not allowed in this game: {}", id)
+            }
+            ActionError::UnknownAction(kind) => write!(f, "Unknown action: {}", kind),
+            ActionError::GameOver => write!(f, "The game is over"),
         }
     }
 }
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use actions::action::{ActionData, ActionKind};
+use actions::movement::Movement;
+use actions::result::ActionResult;
+use actions::shooting::Shooting;
+use models::world::World;
+
+/// Carries out one kind of action. Game modes and mods add actions by
+/// registering a handler for a new kind, without touching the processor.
+pub trait ActionHandler: Send + Sync {
+    fn kind(&self) -> ActionKind;
+
+    /// Checks `action` could be processed, without touching the world.
+    fn validate(&self, world: &World, action: &ActionData) -> ActionResult<()>;
+
+    fn process(&self, world: &mut World, action: &ActionData) -> ActionResult<()>;
+
+    /// Every action of this kind `entity_id` could try, legal or not. Bots
+    /// only consider these.
+    fn candidates(&self, _world: &World, _entity_id: i32) -> Vec<ActionData> {
+        vec![]
+    }
+}
+
+/// The handlers a world processes actions with, keyed by kind. Cheap to
+/// clone: handlers are shared between clones.
+#[derive(Clone, Default)]
+pub struct ActionHandlers {
+    handlers: BTreeMap<ActionKind, Arc<dyn ActionHandler>>,
+}
+
+impl fmt::Debug for ActionHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+impl ActionHandlers {
+    pub fn new() -> ActionHandlers {
+        Default::default()
+    }
+
+    /// Moving and shooting, the actions every world starts with.
+    pub fn builtin() -> ActionHandlers {
+        let mut handlers = ActionHandlers::new();
+        handlers.register(Movement);
+        handlers.register(Shooting);
+        handlers
+    }
+
+    /// Adds `handler`, replacing any other of the same kind.
+    pub fn register<H: ActionHandler + 'static>(&mut self, handler: H) {
+        self.handlers.insert(handler.kind(), Arc::new(handler));
+    }
+
+    pub fn get(&self, kind: ActionKind) -> Option<Arc<dyn ActionHandler>> {
+        self.handlers.get(&kind).cloned()
+    }
+
+    /// The registered kind called `name`, ignoring case as the protocol
+    /// shouts them.
+    pub fn kind(&self, name: &str) -> Option<ActionKind> {
+        self.handlers
+            .keys()
+            .find(|kind| kind.name().eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// Registered kinds, in order.
+    pub fn kinds(&self) -> impl Iterator<Item = ActionKind> + '_ {
+        self.handlers.keys().cloned()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn ActionHandler> {
+        self.handlers.values().map(|handler| handler.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions::{movement, shooting};
+
+    struct Wait;
+
+    impl ActionHandler for Wait {
+        fn kind(&self) -> ActionKind {
+            ActionKind("wait")
+        }
+
+        fn validate(&self, _world: &World, _action: &ActionData) -> ActionResult<()> {
+            Ok(())
+        }
+
+        fn process(&self, _world: &mut World, _action: &ActionData) -> ActionResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn builtin() {
+        let handlers = ActionHandlers::builtin();
+        assert_eq!(
+            handlers.kinds().collect::<Vec<_>>(),
+            vec![movement::MOVE, shooting::SHOOT]
+        );
+        assert_eq!(handlers.kind("SHOOT"), Some(shooting::SHOOT));
+        assert!(handlers.kind("wait").is_none());
+        assert!(handlers.get(ActionKind("wait")).is_none());
+    }
+
+    #[test]
+    fn register() {
+        let mut handlers = ActionHandlers::builtin();
+        handlers.register(Wait);
+        handlers.register(Wait);
+
+        assert_eq!(handlers.iter().count(), 3);
+        assert_eq!(handlers.kind("Wait"), Some(ActionKind("wait")));
+        assert_eq!(
+            format!("{:?}", handlers),
+            "{ActionKind(\"move\"), ActionKind(\"shoot\"), ActionKind(\"wait\")}"
+        );
+    }
+}
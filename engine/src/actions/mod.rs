@@ -1,8 +1,11 @@
 pub mod action;
 pub mod error;
+pub mod handler;
 pub mod movement;
 pub mod processor;
 pub mod result;
+pub mod shooting;
 
-pub use self::action::ActionData;
-pub use self::processor::process_actions;
+pub use self::action::{ActionData, ActionKind, Payload};
+pub use self::handler::{ActionHandler, ActionHandlers};
+pub use self::processor::{legal_actions, process_actions, validate};
@@ -1,42 +1,53 @@
-use actions::action::{ActionData, ActionType};
+use std::mem;
+
+use actions::action::{ActionData, ActionKind, Payload};
 use actions::error::ActionError;
+use actions::handler::ActionHandler;
 use actions::result::ActionResult;
 use models::coordinate::Coordinate;
 use models::direction;
 use models::direction::Direction;
-use models::entity::Entity;
+use models::entity::{Entity, EntityType};
+use models::inventory::Inventory;
+use models::topology::Topology;
 use models::world::World;
 
+pub const MOVE: ActionKind = ActionKind("move");
+
+pub fn towards(entity_id: i32, direction: Direction) -> ActionData {
+    ActionData::new(entity_id, MOVE, Payload::Direction(direction))
+}
+
 pub fn left(entity_id: i32) -> ActionData {
-    ActionData {
-        entity_id,
-        action_type: ActionType::Move,
-        direction: Some(direction::LEFT),
-    }
+    towards(entity_id, direction::LEFT)
 }
 
 pub fn right(entity_id: i32) -> ActionData {
-    ActionData {
-        entity_id,
-        action_type: ActionType::Move,
-        direction: Some(direction::RIGHT),
-    }
+    towards(entity_id, direction::RIGHT)
 }
 
 pub fn up(entity_id: i32) -> ActionData {
-    ActionData {
-        entity_id,
-        action_type: ActionType::Move,
-        direction: Some(direction::UP),
-    }
+    towards(entity_id, direction::UP)
 }
 
 pub fn down(entity_id: i32) -> ActionData {
-    ActionData {
-        entity_id,
-        action_type: ActionType::Move,
-        direction: Some(direction::DOWN),
-    }
+    towards(entity_id, direction::DOWN)
+}
+
+pub fn up_left(entity_id: i32) -> ActionData {
+    towards(entity_id, direction::UP_LEFT)
+}
+
+pub fn up_right(entity_id: i32) -> ActionData {
+    towards(entity_id, direction::UP_RIGHT)
+}
+
+pub fn down_left(entity_id: i32) -> ActionData {
+    towards(entity_id, direction::DOWN_LEFT)
+}
+
+pub fn down_right(entity_id: i32) -> ActionData {
+    towards(entity_id, direction::DOWN_RIGHT)
 }
 
 fn get_entity(world: &World, entity_id: i32) -> ActionResult<Entity> {
@@ -50,19 +61,206 @@ fn get_entity(world: &World, entity_id: i32) -> ActionResult<Entity> {
 pub fn process(world: &mut World, action: ActionData) -> ActionResult<()> {
     let entity = get_entity(world, action.entity_id)?;
 
-    if let Some(dir) = action.direction {
-        let new_coord = operate(entity.coord, dir);
+    if let Some(dir) = action.direction() {
+        let (new_coord, pushed) = destination(world, &entity, dir)?;
+        push(world, &pushed, dir);
+        let loot = pick_up_treasure(world, new_coord);
 
-        is_inside_world(world, new_coord)?;
-        is_position_available(world, new_coord)?;
+        if let Some(inventory) = world.components_mut().inventories.get_mut(entity.id) {
+            *inventory = inventory.merge(loot);
+        }
+        place(world, entity.id, new_coord);
+        drag_followers(world, entity.id, entity.coord);
+    }
+
+    Ok(())
+}
+
+/// Moves `entity_id` to `coord`, returning where it stood.
+fn place(world: &mut World, entity_id: i32, coord: Coordinate) -> Option<Coordinate> {
+    let position = world.components_mut().positions.get_mut(entity_id)?;
+    Some(mem::replace(&mut position.coord, coord))
+}
 
-        let new_entity = entity.with_coordinate(new_coord);
-        world.update_entity(new_entity);
+/// Moves the train behind `leader_id`, each follower stepping into the
+/// tile the one ahead of it left.
+fn drag_followers(world: &mut World, leader_id: i32, vacated: Coordinate) {
+    let (mut leader_id, mut vacated) = (leader_id, vacated);
+    while let Some(follower_id) = follower(world, leader_id) {
+        match place(world, follower_id, vacated) {
+            Some(left) => vacated = left,
+            None => return,
+        }
+        leader_id = follower_id;
+    }
+}
+
+fn follower(world: &World, leader_id: i32) -> Option<i32> {
+    world
+        .components()
+        .controllers
+        .iter()
+        .find(|(_, controller)| controller.leader == Some(leader_id))
+        .map(|(id, _)| id)
+}
+
+/// Checks `action` could be processed, without touching the world.
+pub fn validate(world: &World, action: ActionData) -> ActionResult<()> {
+    let entity = get_entity(world, action.entity_id)?;
+
+    if let Some(dir) = action.direction() {
+        destination(world, &entity, dir)?;
     }
 
     Ok(())
 }
 
+/// Every move `entity_id` could try under the world's rules, legal or not.
+pub fn candidates(world: &World, entity_id: i32) -> Vec<ActionData> {
+    world
+        .rules()
+        .directions()
+        .iter()
+        .map(|dir| towards(entity_id, *dir))
+        .collect()
+}
+
+/// Steps an entity towards its direction, a move without one standing
+/// still.
+pub struct Movement;
+
+impl ActionHandler for Movement {
+    fn kind(&self) -> ActionKind {
+        MOVE
+    }
+
+    fn validate(&self, world: &World, action: &ActionData) -> ActionResult<()> {
+        validate(world, *action)
+    }
+
+    fn process(&self, world: &mut World, action: &ActionData) -> ActionResult<()> {
+        process(world, *action)
+    }
+
+    fn candidates(&self, world: &World, entity_id: i32) -> Vec<ActionData> {
+        candidates(world, entity_id)
+    }
+}
+
+/// Where `entity` ends up stepping towards `dir`, along with the pushables
+/// it shoves out of the way.
+fn destination(
+    world: &World,
+    entity: &Entity,
+    dir: Direction,
+) -> ActionResult<(Coordinate, Vec<Entity>)> {
+    let new_coord = operate(world, entity.coord, dir);
+
+    if entity.leader.is_some() {
+        return Err(ActionError::Towed(entity.id));
+    }
+    if !world.rules().allows(dir) {
+        return Err(ActionError::InvalidDirection(dir.dx, dir.dy));
+    }
+    let mut pushed = vec![];
+    for cell in entity.with_coordinate(new_coord).cells() {
+        is_inside_world(world, cell)?;
+        // Only single tile entities push, a line at a time
+        if entity.cells().len() == 1 && pushable_on(world, cell).is_some() {
+            pushed = pushable_line(world, cell, dir)?
+        } else {
+            is_position_available(world, entity, cell)?
+        }
+    }
+    let topology = world.rules().topology();
+    if !world.rules().corner_cutting
+        && cuts_corner(topology, entity.coord, dir, |c| is_mountain(world, c))
+    {
+        return Err(ActionError::CutsCorner(new_coord.x, new_coord.y));
+    }
+    Ok((new_coord, pushed))
+}
+
+fn pushable_on(world: &World, coord: Coordinate) -> Option<Entity> {
+    world
+        .entities()
+        .find(|entity| entity.occupies(coord) && entity.is_pushable())
+}
+
+fn hole_on(world: &World, coord: Coordinate) -> Option<Entity> {
+    world
+        .entities()
+        .find(|entity| entity.occupies(coord) && entity.is_hole())
+}
+
+/// Holes swallow what's pushed in and passable terrain lets it slide over.
+/// Anything else stops it, treasure included.
+fn stops_pushes(entity: &Entity) -> bool {
+    !entity.is_hole() && !entity.terrain.is_some_and(|terrain| terrain.passable)
+}
+
+/// The pushables lined up from `coord` towards `dir`, provided the rules
+/// allow pushing that many and nothing on the tile past them stops them.
+fn pushable_line(world: &World, coord: Coordinate, dir: Direction) -> ActionResult<Vec<Entity>> {
+    let mut line = vec![];
+    let mut next = coord;
+    while let Some(pushable) = pushable_on(world, next) {
+        line.push(pushable);
+        next = operate(world, next, dir);
+    }
+
+    if line.len() > world.rules().push_limit {
+        return Err(ActionError::TooHeavy(coord.x, coord.y));
+    }
+    is_inside_world(world, next)?;
+    if world
+        .entities()
+        .any(|entity| entity.occupies(next) && stops_pushes(&entity))
+    {
+        return Err(ActionError::PositionOccupied(next.x, next.y));
+    }
+    Ok(line)
+}
+
+/// Shoves a line of pushables a tile towards `dir`, starting from the far
+/// end. One landing in a hole fills it, both disappearing.
+fn push(world: &mut World, line: &[Entity], dir: Direction) {
+    for pushable in line.iter().rev() {
+        let to = operate(world, pushable.coord, dir);
+        let hole = hole_on(world, to).map(|hole| hole.id);
+
+        match hole {
+            Some(hole_id) => {
+                world.remove_entity(hole_id);
+                world.remove_entity(pushable.id);
+            }
+            None => {
+                place(world, pushable.id, to);
+            }
+        }
+    }
+}
+
+fn is_mountain(world: &World, coord: Coordinate) -> bool {
+    world.entities().any(|entity| {
+        entity.occupies(coord) && matches!(entity.entity_type, EntityType::Obstacle(_))
+    })
+}
+
+/// Returns `true` if a step from `from` squeezes past a mountain on either
+/// side, which only diagonal steps on square tiles can do.
+pub fn cuts_corner<F>(
+    topology: &dyn Topology,
+    from: Coordinate,
+    dir: Direction,
+    is_mountain: F,
+) -> bool
+where
+    F: Fn(Coordinate) -> bool,
+{
+    topology.corners(from, dir).into_iter().any(is_mountain)
+}
+
 fn is_inside_world(world: &World, coord: Coordinate) -> ActionResult<()> {
     if !coord.is_within(world.left_edge, world.right_edge) {
         Err(ActionError::OutOfMapCoordinate(coord.x, coord.y))
@@ -71,26 +269,49 @@ fn is_inside_world(world: &World, coord: Coordinate) -> ActionResult<()> {
     }
 }
 
-fn is_position_available(world: &World, coord: Coordinate) -> ActionResult<()> {
-    match world.on_coord(coord) {
-        Some(_) => Err(ActionError::PositionOccupied(coord.x, coord.y)),
-        None => Ok(()),
+/// Removes every treasure lying on `coord`, returning what they held.
+fn pick_up_treasure(world: &mut World, coord: Coordinate) -> Inventory {
+    let treasures: Vec<Entity> = world
+        .entities()
+        .filter(|entity| entity.occupies(coord) && entity.is_treasure())
+        .collect();
+
+    let mut loot = Inventory::default();
+    for treasure in treasures {
+        world.remove_entity(treasure.id);
+        loot = loot.merge(treasure.inventory);
     }
+    loot
 }
 
-pub fn operate(coord: Coordinate, direction: Direction) -> Coordinate {
-    Coordinate {
-        x: coord.x + direction.dx,
-        y: coord.y + direction.dy,
+/// Treasures and passable terrain don't block movement. An entity never
+/// blocks itself.
+fn is_position_available(world: &World, mover: &Entity, coord: Coordinate) -> ActionResult<()> {
+    let blocked = world
+        .entities()
+        .any(|entity| entity.id != mover.id && entity.occupies(coord) && !entity.is_passable());
+
+    if blocked {
+        Err(ActionError::PositionOccupied(coord.x, coord.y))
+    } else {
+        Ok(())
     }
 }
 
+/// Where a step in `direction` from `coord` leads on the world's grid.
+pub fn operate(world: &World, coord: Coordinate, direction: Direction) -> Coordinate {
+    world.rules().topology().step(coord, direction)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use actions::error::ActionError;
+    use models::actors::{barrel, gold, mountain, town, wagon, water};
+    use models::components::Terrain;
     use models::coordinate::Coordinate;
     use models::entity::Entity;
+    use models::rules::Rules;
 
     #[test]
     fn with_invalid_entity() {
@@ -144,6 +365,343 @@ mod tests {
         assert!(new_entity2.coord.is_at_y(2.0));
     }
 
+    #[test]
+    fn blocked_by_a_footprint() {
+        let world: &mut World = &mut World::new();
+        let entity = world.register(Entity::new(0, Coordinate::new(0.0, 1.0)));
+        world.register(town::create_at(Coordinate::new(1.0, 0.0)));
+
+        assert_eq!(
+            process(world, right(entity.id)).err(),
+            Some(ActionError::PositionOccupied(1.0, 1.0))
+        );
+        assert!(process(world, down(entity.id)).is_ok());
+        assert!(process(world, right(entity.id)).is_ok());
+    }
+
+    #[test]
+    fn moving_a_footprint() {
+        let world: &mut World = &mut World::create(Coordinate::new(3.0, 3.0));
+        let big = world.register(Entity {
+            footprint: town::create_at(Coordinate::new(0.0, 0.0)).footprint,
+            ..Entity::new(0, Coordinate::new(0.0, 0.0))
+        });
+        world.register(mountain::create_at(Coordinate::new(2.0, 1.0)));
+
+        assert!(process(world, down(big.id)).is_ok());
+        assert_eq!(
+            process(world, right(big.id)).err(),
+            Some(ActionError::PositionOccupied(2.0, 1.0))
+        );
+        assert!(process(world, down(big.id)).is_ok());
+        assert_eq!(
+            process(world, down(big.id)).err(),
+            Some(ActionError::OutOfMapCoordinate(0.0, 4.0))
+        );
+    }
+
+    #[test]
+    fn wagons_follow() {
+        let world: &mut World = &mut World::create(Coordinate::new(4.0, 4.0));
+        let leader = world.register(Entity::new(0, Coordinate::new(2.0, 0.0)));
+        let first = world.register(wagon::create_behind(&leader, Coordinate::new(1.0, 0.0)));
+        let second = world.register(wagon::create_behind(&first, Coordinate::new(0.0, 0.0)));
+
+        assert!(process(world, down(leader.id)).is_ok());
+        assert_eq!(
+            world.get_entity(first.id).unwrap().coord,
+            Coordinate::new(2.0, 0.0)
+        );
+        assert_eq!(
+            world.get_entity(second.id).unwrap().coord,
+            Coordinate::new(1.0, 0.0)
+        );
+
+        assert_eq!(
+            process(world, up(leader.id)).err(),
+            Some(ActionError::PositionOccupied(2.0, 0.0))
+        );
+        assert_eq!(
+            process(world, left(first.id)).err(),
+            Some(ActionError::Towed(first.id))
+        );
+    }
+
+    #[test]
+    fn push_a_barrel() {
+        let world: &mut World = &mut World::create(Coordinate::new(3.0, 0.0));
+        let entity = world.register(Entity::new(0, Coordinate::new(0.0, 0.0)));
+        let barrel = world.register(barrel::create_at(Coordinate::new(1.0, 0.0)));
+
+        assert!(process(world, right(entity.id)).is_ok());
+        assert_eq!(
+            world.get_entity(entity.id).unwrap().coord,
+            Coordinate::new(1.0, 0.0)
+        );
+        assert_eq!(
+            world.get_entity(barrel.id).unwrap().coord,
+            Coordinate::new(2.0, 0.0)
+        );
+
+        assert!(process(world, right(entity.id)).is_ok());
+        assert_eq!(
+            process(world, right(entity.id)).err(),
+            Some(ActionError::OutOfMapCoordinate(4.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn push_against_something() {
+        let world: &mut World = &mut World::new();
+        let entity = world.register(Entity::new(0, Coordinate::new(0.0, 0.0)));
+        world.register(barrel::create_at(Coordinate::new(1.0, 0.0)));
+        world.register(mountain::create_at(Coordinate::new(2.0, 0.0)));
+        world.register(barrel::create_at(Coordinate::new(0.0, 1.0)));
+        world.register(gold::create_at(Coordinate::new(0.0, 2.0)));
+
+        assert_eq!(
+            process(world, right(entity.id)).err(),
+            Some(ActionError::PositionOccupied(2.0, 0.0))
+        );
+        assert_eq!(
+            process(world, down(entity.id)).err(),
+            Some(ActionError::PositionOccupied(0.0, 2.0))
+        );
+    }
+
+    #[test]
+    fn push_limit() {
+        let world: &mut World = &mut World::new();
+        let entity = world.register(Entity::new(0, Coordinate::new(0.0, 0.0)));
+        world.register(barrel::create_at(Coordinate::new(1.0, 0.0)));
+        let last = world.register(barrel::create_at(Coordinate::new(2.0, 0.0)));
+
+        assert_eq!(
+            process(world, right(entity.id)).err(),
+            Some(ActionError::TooHeavy(1.0, 0.0))
+        );
+
+        world.set_rules(Rules {
+            push_limit: 2,
+            ..Default::default()
+        });
+        assert!(process(world, right(entity.id)).is_ok());
+        assert_eq!(
+            world.get_entity(last.id).unwrap().coord,
+            Coordinate::new(3.0, 0.0)
+        );
+
+        world.set_rules(Rules {
+            push_limit: 0,
+            ..Default::default()
+        });
+        assert_eq!(
+            process(world, right(entity.id)).err(),
+            Some(ActionError::TooHeavy(2.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn fill_a_hole() {
+        let world: &mut World = &mut World::new();
+        let entity = world.register(Entity::new(0, Coordinate::new(0.0, 0.0)));
+        let barrel = world.register(barrel::create_at(Coordinate::new(1.0, 0.0)));
+        let hole = world.register(water::create_at(Coordinate::new(2.0, 0.0)));
+
+        assert!(process(world, right(entity.id)).is_ok());
+        assert!(world.get_entity(barrel.id).is_none());
+        assert!(world.get_entity(hole.id).is_none());
+
+        assert!(process(world, right(entity.id)).is_ok());
+        assert_eq!(
+            world.get_entity(entity.id).unwrap().coord,
+            Coordinate::new(2.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn push_onto_treasure() {
+        let world: &mut World = &mut World::new();
+        let entity = world.register(Entity::new(0, Coordinate::new(0.0, 0.0)));
+        let barrel = world.register(barrel::create_at(Coordinate::new(1.0, 0.0)));
+        // The hole doesn't hide the treasure lying on the same tile
+        world.register(water::create_at(Coordinate::new(2.0, 0.0)));
+        let treasure = world.register(gold::create_at(Coordinate::new(2.0, 0.0)));
+
+        assert_eq!(
+            process(world, right(entity.id)).err(),
+            Some(ActionError::PositionOccupied(2.0, 0.0))
+        );
+        assert_eq!(
+            world.get_entity(barrel.id).unwrap().coord,
+            Coordinate::new(1.0, 0.0)
+        );
+        assert!(world.get_entity(treasure.id).is_some());
+    }
+
+    #[test]
+    fn push_into_a_hole_under_terrain() {
+        let world: &mut World = &mut World::new();
+        let entity = world.register(Entity::new(0, Coordinate::new(0.0, 0.0)));
+        let barrel = world.register(barrel::create_at(Coordinate::new(1.0, 0.0)));
+        let brush = world.register(Entity {
+            terrain: Some(Terrain {
+                passable: true,
+                ..Default::default()
+            }),
+            ..mountain::create_at(Coordinate::new(2.0, 0.0))
+        });
+        let hole = world.register(water::create_at(Coordinate::new(2.0, 0.0)));
+
+        assert!(process(world, right(entity.id)).is_ok());
+        assert!(world.get_entity(barrel.id).is_none());
+        assert!(world.get_entity(hole.id).is_none());
+        assert!(world.get_entity(brush.id).is_some());
+    }
+
+    #[test]
+    fn pick_up_treasure() {
+        let world: &mut World = &mut World::new();
+        let entity = world.register(Entity::new(0, Coordinate::new(1.0, 1.0)));
+        let treasure = world.register(gold::create_at(Coordinate::new(2.0, 1.0)));
+
+        assert!(process(world, right(entity.id)).is_ok());
+
+        let new_entity = world.get_entity(entity.id).unwrap();
+        assert_eq!(new_entity.coord, Coordinate::new(2.0, 1.0));
+        assert_eq!(new_entity.inventory.gold, treasure.inventory.gold);
+        assert!(world.get_entity(treasure.id).is_none());
+    }
+
+    #[test]
+    fn pick_up_treasure_on_terrain() {
+        let world: &mut World = &mut World::new();
+        let entity = world.register(Entity::new(0, Coordinate::new(1.0, 1.0)));
+        world.register(Entity {
+            terrain: Some(Terrain {
+                passable: true,
+                ..Default::default()
+            }),
+            ..mountain::create_at(Coordinate::new(2.0, 1.0))
+        });
+        let first = world.register(gold::create_at(Coordinate::new(2.0, 1.0)));
+        let second = world.register(gold::create_at(Coordinate::new(2.0, 1.0)));
+
+        assert!(process(world, right(entity.id)).is_ok());
+        assert_eq!(
+            world.get_entity(entity.id).unwrap().inventory.gold,
+            first.inventory.gold + second.inventory.gold
+        );
+        assert!(world.get_entity(first.id).is_none());
+        assert!(world.get_entity(second.id).is_none());
+    }
+
+    #[test]
+    fn walk_through_passable_terrain() {
+        let world: &mut World = &mut World::new();
+        let entity = world.register(Entity::new(0, Coordinate::new(1.0, 1.0)));
+        let brush = Entity {
+            terrain: Some(Terrain {
+                passable: true,
+                ..Default::default()
+            }),
+            ..mountain::create_at(Coordinate::new(2.0, 1.0))
+        };
+        world.register(brush);
+        world.register(mountain::create_at(Coordinate::new(3.0, 1.0)));
+
+        assert!(process(world, right(entity.id)).is_ok());
+        assert_eq!(
+            process(world, right(entity.id)).err(),
+            Some(ActionError::PositionOccupied(3.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn validate_leaves_the_world_alone() {
+        let world: &mut World = &mut World::create(Coordinate::new(2.0, 2.0));
+        let entity = world.register(Entity::new(0, Coordinate::new(0.0, 0.0)));
+        world.register(Entity::new(1, Coordinate::new(1.0, 0.0)));
+        let treasure = world.register(gold::create_at(Coordinate::new(0.0, 1.0)));
+        let checksum = world.checksum();
+
+        assert!(validate(world, down(entity.id)).is_ok());
+        assert_eq!(
+            validate(world, right(entity.id)).err(),
+            Some(ActionError::PositionOccupied(1.0, 0.0))
+        );
+        assert_eq!(
+            validate(world, up(entity.id)).err(),
+            Some(ActionError::OutOfMapCoordinate(0.0, -1.0))
+        );
+        assert_eq!(
+            validate(world, up(1234)).err(),
+            Some(ActionError::InvalidEntityId(1234))
+        );
+
+        assert_eq!(world.checksum(), checksum);
+        assert!(world.get_entity(treasure.id).is_some());
+    }
+
+    #[test]
+    fn diagonals() {
+        let world: &mut World = &mut World::create(Coordinate::new(4.0, 4.0));
+        let entity = world.register(Entity::new(0, Coordinate::new(1.0, 1.0)));
+
+        assert_eq!(
+            process(world, down_right(entity.id)).err(),
+            Some(ActionError::InvalidDirection(1.0, 1.0))
+        );
+        assert_eq!(
+            process(world, towards(entity.id, Direction { dx: 2.0, dy: 0.0 })).err(),
+            Some(ActionError::InvalidDirection(2.0, 0.0))
+        );
+
+        world.set_rules(Rules::eight_way(false));
+        assert!(process(world, down_right(entity.id)).is_ok());
+        assert_eq!(
+            world.get_entity(entity.id).unwrap().coord,
+            Coordinate::new(2.0, 2.0)
+        );
+        assert_eq!(candidates(world, entity.id).len(), 8);
+    }
+
+    #[test]
+    fn corner_cutting() {
+        let world: &mut World = &mut World::create(Coordinate::new(4.0, 4.0));
+        world.set_rules(Rules::eight_way(false));
+        let entity = world.register(Entity::new(0, Coordinate::new(1.0, 1.0)));
+        world.register(mountain::create_at(Coordinate::new(2.0, 1.0)));
+        world.register(water::create_at(Coordinate::new(0.0, 1.0)));
+
+        assert_eq!(
+            validate(world, up_right(entity.id)).err(),
+            Some(ActionError::CutsCorner(2.0, 0.0))
+        );
+        assert!(validate(world, up_left(entity.id)).is_ok());
+
+        world.set_rules(Rules::eight_way(true));
+        assert!(process(world, up_right(entity.id)).is_ok());
+    }
+
+    #[test]
+    fn hexes() {
+        let world: &mut World = &mut World::create(Coordinate::new(4.0, 4.0));
+        world.set_rules(Rules::hex());
+        let entity = world.register(Entity::new(0, Coordinate::new(1.0, 1.0)));
+
+        assert_eq!(
+            process(world, down_right(entity.id)).err(),
+            Some(ActionError::InvalidDirection(1.0, 1.0))
+        );
+        assert!(process(world, up_right(entity.id)).is_ok());
+        assert_eq!(
+            world.get_entity(entity.id).unwrap().coord,
+            Coordinate::new(2.0, 0.0)
+        );
+        assert_eq!(candidates(world, entity.id).len(), 6);
+    }
+
     #[test]
     fn world_ends_error() {
         let edge = Coordinate::new(8.0, 8.0);
@@ -1,5 +1,8 @@
-use actions::action::{ActionData, ActionType};
-use actions::movement;
+use std::sync::Arc;
+
+use actions::action::ActionData;
+use actions::error::ActionError;
+use actions::handler::ActionHandler;
 use actions::result::ActionResult;
 use models::world::World;
 
@@ -11,8 +14,194 @@ pub fn process_actions(world: &mut World, actions: &[ActionData]) -> ActionResul
     Ok(())
 }
 
-fn process_action(world: &mut World, action: ActionData) -> ActionResult<()> {
-    match action.action_type {
-        ActionType::Move => movement::process(world, action),
+/// Processes `action` with the handler the world registered for its kind.
+pub fn process_action(world: &mut World, action: ActionData) -> ActionResult<()> {
+    authorize(world, action)?;
+
+    handler(world, &action)?.process(world, &action)
+}
+
+/// Dry run of `action`: returns the error processing it would give, without
+/// mutating the world.
+pub fn validate(world: &World, action: &ActionData) -> ActionResult<()> {
+    authorize(world, *action)?;
+
+    handler(world, action)?.validate(world, action)
+}
+
+/// Actions `entity_id` can take right now, issued by its owner.
+pub fn legal_actions(world: &World, entity_id: i32) -> Vec<ActionData> {
+    let owner = match world.components().controllers.get(entity_id) {
+        Some(controller) => controller.owner,
+        None => return vec![],
+    };
+
+    world
+        .handlers()
+        .iter()
+        .flat_map(|handler| handler.candidates(world, entity_id))
+        .map(|action| action.with_issuer(owner))
+        .filter(|action| validate(world, action).is_ok())
+        .collect()
+}
+
+fn handler(world: &World, action: &ActionData) -> ActionResult<Arc<dyn ActionHandler>> {
+    world
+        .handlers()
+        .get(action.kind)
+        .ok_or(ActionError::UnknownAction(action.kind.name()))
+}
+
+fn authorize(world: &World, action: ActionData) -> ActionResult<()> {
+    match world.components().controllers.get(action.entity_id) {
+        Some(controller) if !action.issuer.controls(controller.owner) => {
+            Err(ActionError::NotOwner(action.entity_id))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions::action::{ActionKind, Payload};
+    use actions::movement;
+    use models::actors::{bandid, mountain, player};
+    use models::coordinate::Coordinate;
+    use models::inventory::Inventory;
+    use models::owner::Owner;
+
+    const RELOAD: ActionKind = ActionKind("reload");
+
+    /// Refills an entity's gun, as a mod would add it.
+    struct Reload;
+
+    impl ActionHandler for Reload {
+        fn kind(&self) -> ActionKind {
+            RELOAD
+        }
+
+        fn validate(&self, world: &World, action: &ActionData) -> ActionResult<()> {
+            match world.components().inventories.get(action.entity_id) {
+                Some(inventory) if inventory.ammo < 6 => Ok(()),
+                Some(_) => Err(ActionError::Forbidden(action.entity_id)),
+                None => Err(ActionError::InvalidEntityId(action.entity_id)),
+            }
+        }
+
+        fn process(&self, world: &mut World, action: &ActionData) -> ActionResult<()> {
+            self.validate(world, action)?;
+            let inventories = &mut world.components_mut().inventories;
+            if let Some(inventory) = inventories.get_mut(action.entity_id) {
+                inventory.ammo += 1;
+            }
+            Ok(())
+        }
+
+        fn candidates(&self, _world: &World, entity_id: i32) -> Vec<ActionData> {
+            vec![ActionData::new(entity_id, RELOAD, Payload::None)]
+        }
+    }
+
+    #[test]
+    fn players_move_their_own_entities() {
+        let mut world = World::new();
+        let entity = world.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+
+        let action = movement::down(entity.id).with_issuer(Owner::Player(1));
+        assert!(process_actions(&mut world, &[action]).is_ok());
+        assert_eq!(
+            world.get_entity(entity.id).unwrap().coord,
+            Coordinate::new(0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn players_cant_move_other_entities() {
+        let mut world = World::new();
+        let other = world.register(player::create_at(2, Coordinate::new(0.0, 0.0)));
+        let bandid = world.register(bandid::create_at(Coordinate::new(2.0, 0.0)));
+        let mountain = world.register(mountain::create_at(Coordinate::new(4.0, 0.0)));
+
+        for id in &[other.id, bandid.id, mountain.id] {
+            let action = movement::down(*id).with_issuer(Owner::Player(1));
+            let result = process_actions(&mut world, &[action]);
+            assert_eq!(result.err(), Some(ActionError::NotOwner(*id)));
+        }
+
+        assert_eq!(
+            world.get_entity(other.id).unwrap().coord,
+            Coordinate::new(0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn validate_checks_ownership() {
+        let mut world = World::new();
+        let entity = world.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+
+        let action = movement::down(entity.id).with_issuer(Owner::Player(2));
+        assert_eq!(
+            validate(&world, &action).err(),
+            Some(ActionError::NotOwner(entity.id))
+        );
+        assert!(validate(&world, &action.with_issuer(Owner::Player(1))).is_ok());
+    }
+
+    #[test]
+    fn legal_actions() {
+        let mut world = World::create(Coordinate::new(2.0, 2.0));
+        let entity = world.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+        world.register(mountain::create_at(Coordinate::new(1.0, 0.0)));
+
+        let actions = super::legal_actions(&world, entity.id);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].issuer, Owner::Player(1));
+        assert!(process_actions(&mut world, &actions).is_ok());
+        assert_eq!(
+            world.get_entity(entity.id).unwrap().coord,
+            Coordinate::new(0.0, 1.0)
+        );
+
+        assert_eq!(super::legal_actions(&world, entity.id).len(), 3);
+        assert!(super::legal_actions(&world, 1234).is_empty());
+    }
+
+    #[test]
+    fn engine_moves_anything() {
+        let mut world = World::new();
+        let bandid = world.register(bandid::create_at(Coordinate::new(2.0, 0.0)));
+
+        let action = movement::down(bandid.id);
+        assert!(process_actions(&mut world, &[action]).is_ok());
+
+        let action = movement::down(bandid.id).with_issuer(Owner::Outlaws);
+        assert!(process_actions(&mut world, &[action]).is_ok());
+    }
+
+    #[test]
+    fn registered_handlers() {
+        let mut world = World::create(Coordinate::new(2.0, 2.0));
+        let entity = world.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+        let reload = ActionData::new(entity.id, RELOAD, Payload::None);
+
+        assert_eq!(
+            process_actions(&mut world, &[reload]).err(),
+            Some(ActionError::UnknownAction("reload"))
+        );
+        assert_eq!(super::legal_actions(&world, entity.id).len(), 2);
+
+        world.register_handler(Reload);
+        assert_eq!(
+            validate(&world, &reload).err(),
+            Some(ActionError::Forbidden(entity.id))
+        );
+        assert_eq!(super::legal_actions(&world, entity.id).len(), 2);
+
+        let shooter = world.get_entity(entity.id).unwrap();
+        world.update_entity(shooter.with_inventory(Inventory::with_ammo(5)));
+        assert_eq!(super::legal_actions(&world, entity.id).len(), 3);
+        assert!(process_actions(&mut world, &[reload]).is_ok());
+        assert_eq!(world.get_entity(entity.id).unwrap().inventory.ammo, 6);
     }
 }
@@ -0,0 +1,290 @@
+use actions::action::{ActionData, ActionKind, Payload};
+use actions::error::ActionError;
+use actions::handler::ActionHandler;
+use actions::result::ActionResult;
+use models::entity::{Entity, EntityType};
+use models::fov;
+use models::world::World;
+
+/// How far guns reach by default, in steps on the world's grid.
+pub const RANGE: i32 = 6;
+
+/// Default chance of hitting a target in the open.
+pub const HIT_CHANCE: f32 = 0.7;
+
+/// Health a hit takes off its target by default.
+pub const DAMAGE: i32 = 1;
+
+/// Taken off the hit chance when the target stands next to a mountain.
+pub const COVER_BONUS: f32 = 0.3;
+
+pub const SHOOT: ActionKind = ActionKind("shoot");
+
+pub fn at(entity_id: i32, target_id: i32) -> ActionData {
+    ActionData::new(entity_id, SHOOT, Payload::Target(target_id))
+}
+
+fn is_shootable(entity: &Entity) -> bool {
+    matches!(
+        entity.entity_type,
+        EntityType::Player(_) | EntityType::Enemy(_)
+    )
+}
+
+/// Every shot `entity_id` could try, legal or not.
+pub fn candidates(world: &World, entity_id: i32) -> Vec<ActionData> {
+    world
+        .entities()
+        .filter(|entity| entity.id != entity_id && is_shootable(entity))
+        .map(|entity| at(entity_id, entity.id))
+        .collect()
+}
+
+/// Returns the shooter and its target if `action` is a shot that can be
+/// taken.
+fn aim(world: &World, action: ActionData) -> ActionResult<(Entity, Entity)> {
+    let shooter = world
+        .get_entity(action.entity_id)
+        .ok_or(ActionError::InvalidEntityId(action.entity_id))?;
+    let target_id = action.target().ok_or(ActionError::NoTarget)?;
+    let target = world
+        .get_entity(target_id)
+        .ok_or(ActionError::InvalidEntityId(target_id))?;
+
+    if target.id == shooter.id || !is_shootable(&target) {
+        return Err(ActionError::InvalidTarget(target.id));
+    }
+    if shooter.inventory.ammo <= 0 {
+        return Err(ActionError::OutOfAmmo(shooter.id));
+    }
+    if world
+        .rules()
+        .topology()
+        .distance(shooter.coord, target.coord)
+        > world.rules().shot_range
+    {
+        return Err(ActionError::OutOfRange(target.id));
+    }
+    if !fov::has_line_of_sight(world, shooter.coord, target.coord) {
+        return Err(ActionError::NoLineOfSight(target.id));
+    }
+
+    Ok((shooter, target))
+}
+
+pub fn validate(world: &World, action: ActionData) -> ActionResult<()> {
+    aim(world, action).map(|_| ())
+}
+
+/// Chance of hitting `target`, lower when it's in cover.
+pub fn hit_chance(world: &World, target: &Entity) -> f32 {
+    let topology = world.rules().topology();
+    let in_cover = world.entities().any(|entity| {
+        matches!(entity.entity_type, EntityType::Obstacle(_))
+            && entity
+                .cells()
+                .into_iter()
+                .any(|cell| cell != target.coord && topology.is_adjacent(cell, target.coord))
+    });
+
+    let rules = world.rules();
+    if in_cover {
+        rules.hit_chance - rules.cover_bonus
+    } else {
+        rules.hit_chance
+    }
+}
+
+/// Fires one round at the target, taking the rules' damage off its health on
+/// a hit.
+/// Targets without health go down on the first hit; the deaths system
+/// removes them.
+pub fn process(world: &mut World, action: ActionData) -> ActionResult<()> {
+    let (shooter, target) = aim(world, action)?;
+
+    if let Some(inventory) = world.components_mut().inventories.get_mut(shooter.id) {
+        inventory.ammo -= 1;
+    }
+
+    let chance = hit_chance(world, &target);
+    if world.rng_mut().chance(chance) {
+        let health = target
+            .health
+            .unwrap_or_default()
+            .damaged(world.rules().damage);
+        world.components_mut().healths.insert(target.id, health);
+    }
+
+    Ok(())
+}
+
+/// Fires at the target entity.
+pub struct Shooting;
+
+impl ActionHandler for Shooting {
+    fn kind(&self) -> ActionKind {
+        SHOOT
+    }
+
+    fn validate(&self, world: &World, action: &ActionData) -> ActionResult<()> {
+        validate(world, *action)
+    }
+
+    fn process(&self, world: &mut World, action: &ActionData) -> ActionResult<()> {
+        process(world, *action)
+    }
+
+    fn candidates(&self, world: &World, entity_id: i32) -> Vec<ActionData> {
+        candidates(world, entity_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::components::Health;
+    use models::rules::Rules;
+    use serializers::basic;
+
+    // Player 1 is always entity 1 and player 2 the last one in these maps
+    fn shoot(map: &str, seed: u64) -> (World, ActionResult<()>) {
+        let mut world = basic::load(map);
+        world.reseed(seed);
+        let target = world.entities().last().unwrap().id;
+        let result = process(&mut world, at(1, target));
+        (world, result)
+    }
+
+    #[test]
+    fn uses_ammo() {
+        let (world, result) = shoot("1   2", 1);
+        assert!(result.is_ok());
+        assert_eq!(world.get_entity(1).unwrap().inventory.ammo, 5);
+    }
+
+    #[test]
+    fn out_of_ammo() {
+        let mut world = basic::load("1   2");
+        let shooter = world.get_entity(1).unwrap();
+        world.update_entity(shooter.with_inventory(Default::default()));
+
+        assert_eq!(
+            process(&mut world, at(1, 2)).err(),
+            Some(ActionError::OutOfAmmo(1))
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        let (world, result) = shoot("1      2", 1);
+        assert_eq!(result.err(), Some(ActionError::OutOfRange(2)));
+        assert_eq!(world.get_entity(1).unwrap().inventory.ammo, 6);
+    }
+
+    #[test]
+    fn range_comes_from_the_rules() {
+        let mut world = basic::load("1      2");
+        world.set_rules(Rules {
+            shot_range: 7,
+            ..Default::default()
+        });
+        assert!(process(&mut world, at(1, 2)).is_ok());
+    }
+
+    #[test]
+    fn blocked_by_mountains() {
+        let (_, result) = shoot("1 # 2", 1);
+        assert_eq!(result.err(), Some(ActionError::NoLineOfSight(3)));
+
+        let (_, result) = shoot("1 ~ 2", 1);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn invalid_targets() {
+        let mut world = basic::load("1 # 2");
+        assert_eq!(
+            process(&mut world, at(1, 2)).err(),
+            Some(ActionError::InvalidTarget(2))
+        );
+        assert_eq!(
+            process(&mut world, at(1, 1)).err(),
+            Some(ActionError::InvalidTarget(1))
+        );
+        assert_eq!(
+            process(&mut world, at(1, 9)).err(),
+            Some(ActionError::InvalidEntityId(9))
+        );
+
+        let mut no_target = at(1, 3);
+        no_target.payload = Payload::None;
+        assert_eq!(
+            process(&mut world, no_target).err(),
+            Some(ActionError::NoTarget)
+        );
+    }
+
+    #[test]
+    fn cover() {
+        let world = basic::load(&["1   2", "    #"].join("\n"));
+        let target = world.get_entity(2).unwrap();
+        assert!((hit_chance(&world, &target) - (HIT_CHANCE - COVER_BONUS)).abs() < 1e-6);
+
+        let shooter = world.get_entity(1).unwrap();
+        assert!((hit_chance(&world, &shooter) - HIT_CHANCE).abs() < 1e-6);
+    }
+
+    #[test]
+    fn hits_are_random_but_reproducible() {
+        let mut hits = 0;
+        for seed in 0..100 {
+            let (world, result) = shoot("1  2", seed);
+            assert!(result.is_ok());
+            let (again, _) = shoot("1  2", seed);
+            assert_eq!(world.checksum(), again.checksum());
+
+            if world.get_entity(2).unwrap().health.unwrap().is_dead() {
+                hits += 1;
+            }
+        }
+
+        assert!(hits > 50 && hits < 90, "{} hits", hits);
+    }
+
+    #[test]
+    fn hits_take_health() {
+        let mut world = basic::load("1  2");
+        let target = world.get_entity(2).unwrap();
+        world.update_entity(Entity {
+            health: Some(Health::new(3)),
+            ..target
+        });
+
+        for seed in 0..6 {
+            world.reseed(seed);
+            assert!(process(&mut world, at(1, 2)).is_ok());
+        }
+
+        let health = world.get_entity(2).unwrap().health.unwrap();
+        assert!(health.current < 3);
+        assert_eq!(health.max, 3);
+    }
+
+    #[test]
+    fn damage_comes_from_the_rules() {
+        let mut world = basic::load("1  2");
+        world.set_rules(Rules {
+            hit_chance: 1.0,
+            damage: 2,
+            ..Default::default()
+        });
+        let target = world.get_entity(2).unwrap();
+        world.update_entity(Entity {
+            health: Some(Health::new(3)),
+            ..target
+        });
+
+        assert!(process(&mut world, at(1, 2)).is_ok());
+        assert_eq!(world.get_entity(2).unwrap().health.unwrap().current, 1);
+    }
+}
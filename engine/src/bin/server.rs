@@ -0,0 +1,110 @@
+extern crate engine;
+
+use std::env;
+use std::fs;
+use std::process;
+
+use engine::game;
+use engine::models::actors::player;
+use engine::models::{Prefabs, Rules};
+use engine::net::Server;
+use engine::serializers::{basic, prefabs, rules};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:7878";
+
+/// Reads the rules file at `path`, which replaces the map's own rules.
+fn load_rules(path: &str) -> Rules {
+    let raw = fs::read_to_string(path).unwrap_or_else(|error| {
+        eprintln!("Can't read rules {}: {}", path, error);
+        process::exit(1);
+    });
+    rules::load(&raw).unwrap_or_else(|error| {
+        eprintln!("Invalid rules {}: {}", path, error);
+        process::exit(1);
+    })
+}
+
+/// Reads the actors file at `path`, which maps are drawn with.
+fn load_actors(path: &str) -> Prefabs {
+    let raw = fs::read_to_string(path).unwrap_or_else(|error| {
+        eprintln!("Can't read actors {}: {}", path, error);
+        process::exit(1);
+    });
+    prefabs::load(&raw).unwrap_or_else(|error| {
+        eprintln!("Invalid actors {}: {}", path, error);
+        process::exit(1);
+    })
+}
+
+/// Value following the `name` option, as in `--rules rules.txt`.
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|index| args.get(index + 1))
+        .map(String::as_str)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    // Arguments left once options and their values are taken out
+    let mut positional = vec![];
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        if arg.starts_with("--") {
+            rest.next();
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+
+    if positional.is_empty() {
+        eprintln!(
+            "Usage: {} <map-file> [players] [address] [--mode classic|race|deathmatch] \
+             [--rules <file>] [--actors <file>]",
+            args[0]
+        );
+        process::exit(1);
+    }
+
+    let raw = fs::read_to_string(positional[0]).unwrap_or_else(|error| {
+        eprintln!("Can't read map {}: {}", positional[0], error);
+        process::exit(1);
+    });
+    let players: usize = positional.get(1).and_then(|n| n.parse().ok()).unwrap_or(2);
+    if players < 1 || players > player::MAX as usize {
+        eprintln!("Between 1 and {} players can play", player::MAX);
+        process::exit(1);
+    }
+    let addr = positional.get(2).cloned().unwrap_or(DEFAULT_ADDR);
+
+    let actors = match option(&args, "--actors") {
+        Some(path) => load_actors(path),
+        None => Prefabs::builtin().clone(),
+    };
+    let mut world = basic::load_with(raw.trim_end_matches('\n'), &actors);
+    if let Some(path) = option(&args, "--rules") {
+        world.set_rules(load_rules(path));
+    }
+
+    let mode = option(&args, "--mode").unwrap_or("classic");
+    let mode = game::mode::by_name(mode).unwrap_or_else(|| {
+        eprintln!("Unknown game mode `{}`", mode);
+        process::exit(1);
+    });
+
+    let mut server = Server::bind_with(addr, world, mode).unwrap_or_else(|error| {
+        eprintln!("Can't listen on {}: {}", addr, error);
+        process::exit(1);
+    });
+
+    println!("Waiting for {} players on {}", players, addr);
+    let result = server.accept_players(players).and_then(|_| server.run());
+
+    match result {
+        Ok(_) => println!("Game over after {} turns.", server.turn()),
+        Err(error) => {
+            eprintln!("Server error: {}", error);
+            process::exit(1);
+        }
+    }
+}
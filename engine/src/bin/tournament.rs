@@ -0,0 +1,70 @@
+extern crate engine;
+
+use std::env;
+use std::fs;
+use std::process;
+
+use engine::game::tournament::{self, Tournament};
+
+const USAGE: &str = "Usage: tournament --maps <file,...> --controllers <name,...> \
+                     [--mode classic|race|deathmatch] [--matches N] [--turns N] [--seed N] \
+                     [--format csv|json]";
+
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|index| args.get(index + 1))
+        .map(String::as_str)
+}
+
+fn number<T: std::str::FromStr>(args: &[String], name: &str, default: T) -> T {
+    match option(args, name) {
+        Some(raw) => raw
+            .parse()
+            .unwrap_or_else(|_| fail(&format!("Invalid {}: {}", name, raw))),
+        None => default,
+    }
+}
+
+fn list(args: &[String], name: &str) -> Vec<String> {
+    match option(args, name) {
+        Some(raw) => raw.split(',').map(String::from).collect(),
+        None => fail(USAGE),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let maps = list(&args, "--maps")
+        .iter()
+        .map(|path| match fs::read_to_string(path) {
+            Ok(raw) => raw.trim_end_matches('\n').to_string(),
+            Err(error) => fail(&format!("Can't read map {}: {}", path, error)),
+        })
+        .collect();
+
+    let tournament = Tournament {
+        maps,
+        controllers: list(&args, "--controllers"),
+        mode: option(&args, "--mode").unwrap_or("classic").to_string(),
+        matches: number(&args, "--matches", 10),
+        turn_limit: number(&args, "--turns", 200),
+        seed: number(&args, "--seed", 0),
+    };
+
+    let standings = tournament
+        .play()
+        .unwrap_or_else(|error| fail(&error.to_string()));
+
+    match option(&args, "--format").unwrap_or("csv") {
+        "csv" => println!("{}", tournament::to_csv(&standings)),
+        "json" => println!("{}", tournament::to_json(&standings)),
+        format => fail(&format!("Unknown format: {}", format)),
+    }
+}
@@ -0,0 +1,12 @@
+use actions::action::ActionData;
+use models::view::WorldView;
+
+/// Decides what an entity does each turn: a human at the keyboard, a
+/// script or a bot.
+pub trait Controller {
+    /// Returns the action for `entity_id` this turn, or `None` to wait.
+    fn decide(&mut self, view: &WorldView, entity_id: i32) -> Option<ActionData>;
+
+    /// Told the name of the mode being played, for bots scoring by it.
+    fn play_mode(&mut self, _mode: &'static str) {}
+}
@@ -0,0 +1,69 @@
+use actions::action::ActionData;
+use actions::movement;
+use controllers::controller::Controller;
+use controllers::pathfinding;
+use models::view::WorldView;
+
+/// Heads for the closest treasure it can reach, exploring when it sees
+/// none.
+#[derive(Default)]
+pub struct Greedy {}
+
+impl Greedy {
+    pub fn new() -> Greedy {
+        Greedy {}
+    }
+}
+
+impl Controller for Greedy {
+    fn decide(&mut self, view: &WorldView, entity_id: i32) -> Option<ActionData> {
+        let entity = view.get_entity(entity_id)?;
+        let is_treasure = |coord| {
+            view.entities()
+                .any(|other| other.is_treasure() && other.coord == coord)
+        };
+
+        pathfinding::first_step(view, entity.coord, is_treasure)
+            .or_else(|| pathfinding::first_step(view, entity.coord, |c| !view.is_explored(c)))
+            .map(|dir| movement::towards(entity_id, dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::runner;
+    use serializers::basic;
+
+    #[test]
+    fn collects_the_treasure() {
+        let mut world = basic::load(&["1 #  ", "  # $", "     "].join("\n"));
+        let mut greedy = Greedy::new();
+
+        for _ in 0..10 {
+            if let Some(action) = greedy.decide(&WorldView::new(&world), 1) {
+                world.register_action(action);
+                assert!(runner::run(&mut world).is_ok());
+            }
+        }
+
+        assert_eq!(world.get_entity(1).unwrap().inventory.gold, 10);
+        assert!(greedy.decide(&WorldView::new(&world), 1).is_none());
+    }
+
+    #[test]
+    fn explores_the_fog() {
+        let mut world = basic::load(&["1  #         $", "              "].join("\n"));
+        let mut greedy = Greedy::new();
+
+        for _ in 0..20 {
+            let view = WorldView::for_player(&world, 1);
+            if let Some(action) = greedy.decide(&view, 1) {
+                world.register_action(action);
+                let _ = runner::run(&mut world);
+            }
+        }
+
+        assert_eq!(world.get_entity(1).unwrap().inventory.gold, 10);
+    }
+}
@@ -0,0 +1,119 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use actions::action::ActionData;
+use actions::{movement, shooting};
+use controllers::controller::Controller;
+use models::direction::Direction;
+use models::view::WorldView;
+
+#[derive(Clone, Copy, Debug)]
+enum Command {
+    Move(Direction),
+    Shoot,
+}
+
+/// Handle the frontend keeps to feed key presses to a `Keyboard`.
+#[derive(Clone, Default)]
+pub struct KeyboardInput {
+    pending: Rc<Cell<Option<Command>>>,
+}
+
+impl KeyboardInput {
+    pub fn new() -> KeyboardInput {
+        Default::default()
+    }
+
+    pub fn press(&self, direction: Direction) {
+        self.pending.set(Some(Command::Move(direction)));
+    }
+
+    /// Shoots at the closest target in range.
+    pub fn shoot(&self) {
+        self.pending.set(Some(Command::Shoot));
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.get().is_some()
+    }
+}
+
+/// Plays the last key pressed, then waits for the next one.
+pub struct Keyboard {
+    input: KeyboardInput,
+}
+
+impl Keyboard {
+    pub fn new(input: KeyboardInput) -> Keyboard {
+        Keyboard { input }
+    }
+}
+
+impl Controller for Keyboard {
+    fn decide(&mut self, view: &WorldView, entity_id: i32) -> Option<ActionData> {
+        match self.input.pending.take()? {
+            Command::Move(dir) => Some(movement::towards(entity_id, dir)),
+            Command::Shoot => closest_shot(view, entity_id),
+        }
+    }
+}
+
+/// The legal shot at the closest target, the lowest id on ties.
+pub fn closest_shot(view: &WorldView, entity_id: i32) -> Option<ActionData> {
+    let from = view.get_entity(entity_id)?.coord;
+    let distance = |action: &ActionData| {
+        let to = view.get_entity(action.target()?)?.coord;
+        Some((to.x - from.x).powi(2) + (to.y - from.y).powi(2))
+    };
+
+    view.legal_actions(entity_id)
+        .into_iter()
+        .filter(|action| action.kind == shooting::SHOOT)
+        .filter_map(|action| Some((distance(&action)?, action)))
+        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal))
+        .map(|(_, action)| action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::direction;
+    use models::world::World;
+    use serializers::basic;
+
+    #[test]
+    fn one_action_per_key() {
+        let world = World::new();
+        let view = WorldView::new(&world);
+        let input = KeyboardInput::new();
+        let mut keyboard = Keyboard::new(input.clone());
+
+        assert!(keyboard.decide(&view, 1).is_none());
+
+        input.press(direction::UP);
+        assert!(input.is_pending());
+
+        let action = keyboard.decide(&view, 1).unwrap();
+        assert_eq!(action.entity_id, 1);
+        assert!(!input.is_pending());
+        assert!(keyboard.decide(&view, 1).is_none());
+    }
+
+    #[test]
+    fn shoots_the_closest_target() {
+        let world = basic::load(&["B 1  2", "      "].join("\n"));
+        let view = WorldView::for_player(&world, 1);
+        let input = KeyboardInput::new();
+        let mut keyboard = Keyboard::new(input.clone());
+
+        input.shoot();
+        let action = keyboard.decide(&view, 2).unwrap();
+        assert_eq!(action.target(), Some(1));
+
+        let lonely = basic::load("1");
+        input.shoot();
+        assert!(keyboard.decide(&WorldView::new(&lonely), 1).is_none());
+        assert!(!input.is_pending());
+    }
+}
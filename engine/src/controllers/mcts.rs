@@ -0,0 +1,363 @@
+use std::time::{Duration, Instant};
+
+use actions::action::ActionData;
+use actions::movement;
+use actions::processor;
+use controllers::controller::Controller;
+use controllers::pathfinding;
+use game::mode::{self, Classic, GameMode};
+use game::systems;
+use game::victory::GameState;
+use models::owner::Owner;
+use models::rng::Rng;
+use models::view::WorldView;
+use models::world::World;
+
+/// How long the search runs before deciding.
+#[derive(Clone, Copy, Debug)]
+pub enum Budget {
+    Iterations(u32),
+    Time(Duration),
+}
+
+impl Budget {
+    fn allows(self, iterations: u32, start: Instant) -> bool {
+        match self {
+            Budget::Iterations(limit) => iterations < limit,
+            Budget::Time(limit) => iterations == 0 || start.elapsed() < limit,
+        }
+    }
+}
+
+struct Node {
+    parent: Option<usize>,
+    action: Option<ActionData>,
+    children: Vec<usize>,
+    untried: Vec<ActionData>,
+    visits: u32,
+    value: f32,
+}
+
+impl Node {
+    fn new(parent: Option<usize>, action: Option<ActionData>, untried: Vec<ActionData>) -> Node {
+        Node {
+            parent,
+            action,
+            children: vec![],
+            untried,
+            visits: 0,
+            value: 0.0,
+        }
+    }
+}
+
+/// Monte Carlo tree search over clones of the world, maximising the score
+/// the mode played gives within `depth` turns. Points scored sooner are
+/// worth more. Other entities are assumed to stand still while searching,
+/// and only what the view shows is searched: with nothing but moves to make
+/// and no treasure in sight, it explores.
+pub struct Mcts {
+    rng: Rng,
+    budget: Budget,
+    depth: u32,
+    discount: f32,
+    exploration: f32,
+    mode: &'static str,
+}
+
+impl Mcts {
+    pub fn new(seed: u64, budget: Budget) -> Mcts {
+        Mcts {
+            rng: Rng::new(seed),
+            budget,
+            depth: 12,
+            discount: 0.9,
+            exploration: 1.4,
+            mode: Classic.name(),
+        }
+    }
+
+    pub fn with_depth(self, depth: u32) -> Mcts {
+        Mcts { depth, ..self }
+    }
+
+    fn select(&self, nodes: &[Node], parent: usize) -> usize {
+        let log_visits = (nodes[parent].visits as f32).ln();
+        let uct = |child: usize| {
+            let node = &nodes[child];
+            let visits = node.visits as f32;
+            node.value / visits + self.exploration * (log_visits / visits).sqrt()
+        };
+
+        let mut best = nodes[parent].children[0];
+        for child in nodes[parent].children.iter().skip(1) {
+            if uct(*child) > uct(best) {
+                best = *child;
+            }
+        }
+        best
+    }
+
+    /// Plays `action` as a turn of its own, returning the discounted points
+    /// it scored.
+    fn step(
+        &self,
+        world: &mut World,
+        mode: &mut dyn GameMode,
+        entity_id: i32,
+        action: ActionData,
+        depth: u32,
+    ) -> f32 {
+        let before = score(world, mode, entity_id);
+        mode.on_turn_start(world);
+        // Only legal actions get here
+        let processed = processor::process_actions(world, &[action]);
+        systems::after_action(world);
+        if processed.is_ok() {
+            mode.on_action_applied(world, &action);
+        }
+        (score(world, mode, entity_id) - before) as f32 * self.discount.powi(depth as i32)
+    }
+
+    fn iterate(&mut self, root: &World, entity_id: i32, nodes: &mut Vec<Node>, scale: f32) {
+        // Chance plays out differently every time, not as the real game will
+        let mut world = root.clone();
+        world.reseed(self.rng.next_u64());
+        let mut mode = playout_mode(self.mode);
+        let mode = mode.as_mut();
+        let mut node = 0;
+        let mut depth = 0;
+        let mut reward = 0.0;
+
+        while nodes[node].untried.is_empty() && !nodes[node].children.is_empty() {
+            node = self.select(nodes, node);
+            let action = nodes[node].action.unwrap();
+            reward += self.step(&mut world, mode, entity_id, action, depth);
+            depth += 1;
+        }
+
+        if !nodes[node].untried.is_empty() {
+            let index = self.rng.below(nodes[node].untried.len() as u32) as usize;
+            let action = nodes[node].untried.swap_remove(index);
+            reward += self.step(&mut world, mode, entity_id, action, depth);
+            depth += 1;
+
+            let untried = if depth < self.depth && is_running(&world, mode) {
+                legal_actions(&world, mode, entity_id)
+            } else {
+                vec![]
+            };
+            nodes.push(Node::new(Some(node), Some(action), untried));
+            let child = nodes.len() - 1;
+            nodes[node].children.push(child);
+            node = child;
+        }
+
+        while depth < self.depth && is_running(&world, mode) {
+            let legal = legal_actions(&world, mode, entity_id);
+            let action = match self.rng.choose(&legal) {
+                Some(action) => *action,
+                None => break,
+            };
+            reward += self.step(&mut world, mode, entity_id, action, depth);
+            depth += 1;
+        }
+
+        let mut current = Some(node);
+        while let Some(index) = current {
+            nodes[index].visits += 1;
+            nodes[index].value += reward / scale;
+            current = nodes[index].parent;
+        }
+    }
+}
+
+/// A fresh copy of the mode called `name` to play out a search with, modes
+/// it doesn't know being played as classic.
+fn playout_mode(name: &str) -> Box<dyn GameMode> {
+    mode::by_name(name).unwrap_or_else(|| Box::new(Classic))
+}
+
+/// The mode's score of the player owning `entity_id`, or the gold it
+/// carries when no player owns it.
+fn score(world: &World, mode: &dyn GameMode, entity_id: i32) -> i32 {
+    match world.get_entity(entity_id) {
+        Some(entity) => match entity.owner {
+            Owner::Player(player) => mode.score(world, player),
+            _ => entity.inventory.gold,
+        },
+        None => 0,
+    }
+}
+
+fn is_running(world: &World, mode: &dyn GameMode) -> bool {
+    mode.check_victory(world) == GameState::Running
+}
+
+/// Legal actions the mode allows too.
+fn legal_actions(world: &World, mode: &dyn GameMode, entity_id: i32) -> Vec<ActionData> {
+    processor::legal_actions(world, entity_id)
+        .into_iter()
+        .filter(|action| mode.validate_action(world, action).is_ok())
+        .collect()
+}
+
+fn has_treasure(world: &World) -> bool {
+    world.entities().any(|entity| entity.is_treasure())
+}
+
+impl Controller for Mcts {
+    fn decide(&mut self, view: &WorldView, entity_id: i32) -> Option<ActionData> {
+        let world = view.snapshot();
+        let legal = legal_actions(&world, playout_mode(self.mode).as_ref(), entity_id);
+        let first = legal.first().cloned();
+        if legal.len() <= 1 {
+            return first;
+        }
+
+        // Nothing to search for in sight, go explore
+        let only_moves = legal.iter().all(|action| action.kind == movement::MOVE);
+        if only_moves && !has_treasure(&world) {
+            let entity = view.get_entity(entity_id)?;
+            return pathfinding::first_step(view, entity.coord, |c| !view.is_explored(c))
+                .map(|dir| movement::towards(entity_id, dir).with_issuer(entity.owner));
+        }
+
+        // Keeps node values around [0, 1]: all the gold and every player in
+        // sight is as much as can be scored
+        let total: i32 = world
+            .entities()
+            .filter(|entity| entity.is_treasure() || entity.is_player())
+            .map(|entity| entity.inventory.gold.max(1))
+            .sum();
+        let scale = total.max(1) as f32;
+
+        let mut nodes = vec![Node::new(None, None, legal)];
+        let start = Instant::now();
+        let mut iterations = 0;
+        while self.budget.allows(iterations, start) {
+            self.iterate(&world, entity_id, &mut nodes, scale);
+            iterations += 1;
+        }
+
+        // The most visited child, the first of them on a tie
+        let best = nodes[0]
+            .children
+            .iter()
+            .rev()
+            .max_by_key(|child| nodes[**child].visits);
+        // Without a single iteration there's nothing to go by
+        best.map_or(first, |best| nodes[*best].action)
+    }
+
+    fn play_mode(&mut self, mode: &'static str) {
+        self.mode = mode;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions::shooting;
+    use controllers::greedy::Greedy;
+    use models::coordinate::Coordinate;
+    use models::direction::{self, Direction};
+    use serializers::basic;
+
+    fn player(world: &World) -> i32 {
+        world.find_player(1).unwrap().id
+    }
+
+    fn decide(map: &str, budget: Budget) -> Direction {
+        let world = basic::load(map);
+        let mut mcts = Mcts::new(3, budget);
+        mcts.decide(&WorldView::new(&world), player(&world))
+            .and_then(|action| action.direction())
+            .unwrap()
+    }
+
+    fn is_dir(dir: Direction, expected: Direction) -> bool {
+        Coordinate::new(dir.dx, dir.dy) == Coordinate::new(expected.dx, expected.dy)
+    }
+
+    #[test]
+    fn takes_the_adjacent_pile() {
+        let map = ["     ", "  1$ ", "     "].join("\n");
+        assert!(is_dir(
+            decide(&map, Budget::Iterations(300)),
+            direction::RIGHT
+        ));
+    }
+
+    #[test]
+    fn prefers_the_bigger_hoard() {
+        // The greedy bot heads for the closest pile
+        let map = "$ 1  $$$ ";
+        let world = basic::load(map);
+        let greedy = Greedy::new()
+            .decide(&WorldView::new(&world), player(&world))
+            .unwrap();
+        assert!(is_dir(greedy.direction().unwrap(), direction::LEFT));
+
+        assert!(is_dir(
+            decide(map, Budget::Iterations(2000)),
+            direction::RIGHT
+        ));
+    }
+
+    #[test]
+    fn finds_the_way_around_walls() {
+        let map = ["   # ", "  1#$", "   # ", "     "].join("\n");
+        assert!(is_dir(
+            decide(&map, Budget::Iterations(2000)),
+            direction::DOWN
+        ));
+    }
+
+    #[test]
+    fn time_budget() {
+        let map = ["     ", "  1$ ", "     "].join("\n");
+        let budget = Budget::Time(Duration::from_millis(20));
+        assert!(is_dir(decide(&map, budget), direction::RIGHT));
+    }
+
+    #[test]
+    fn only_move() {
+        let world = basic::load(&["1#", "  "].join("\n"));
+        let mut mcts = Mcts::new(3, Budget::Iterations(0));
+        let action = mcts.decide(&WorldView::new(&world), 1).unwrap();
+        assert!(is_dir(action.direction().unwrap(), direction::DOWN));
+    }
+
+    #[test]
+    fn no_iterations() {
+        let world = basic::load(&["     ", "  1$ ", "     "].join("\n"));
+        let mut mcts = Mcts::new(3, Budget::Iterations(0));
+        let view = WorldView::new(&world);
+        assert_eq!(
+            mcts.decide(&view, 1).map(|action| action.kind),
+            processor::legal_actions(&world, 1)
+                .first()
+                .map(|action| action.kind)
+        );
+    }
+
+    #[test]
+    fn plays_for_the_mode() {
+        // No treasure to go for, but a deathmatch is won by shooting
+        let world = basic::load("1 2");
+        let mut mcts = Mcts::new(3, Budget::Iterations(300)).with_depth(3);
+        mcts.play_mode("deathmatch");
+
+        let action = mcts.decide(&WorldView::new(&world), 1).unwrap();
+        assert_eq!(action.kind, shooting::SHOOT);
+        assert_eq!(action.target(), Some(2));
+    }
+
+    #[test]
+    fn stuck() {
+        let world = basic::load("1");
+        let mut mcts = Mcts::new(3, Budget::Iterations(100));
+        assert!(mcts.decide(&WorldView::new(&world), 1).is_none());
+    }
+}
@@ -0,0 +1,29 @@
+pub mod controller;
+pub mod greedy;
+pub mod keyboard;
+pub mod mcts;
+pub mod pathfinding;
+pub mod random;
+pub mod scripted;
+
+pub use self::controller::Controller;
+pub use self::greedy::Greedy;
+pub use self::keyboard::{Keyboard, KeyboardInput};
+pub use self::mcts::{Budget, Mcts};
+pub use self::random::Random;
+pub use self::scripted::Scripted;
+
+const MCTS_ITERATIONS: u32 = 500;
+
+/// Builds one of the bots by name, as used on command lines.
+pub fn by_name(name: &str, seed: u64) -> Option<Box<dyn Controller>> {
+    match name {
+        "random" => Some(Box::new(Random::new(seed))),
+        "greedy" => Some(Box::new(Greedy::new())),
+        "mcts" => Some(Box::new(Mcts::new(
+            seed,
+            Budget::Iterations(MCTS_ITERATIONS),
+        ))),
+        _ => None,
+    }
+}
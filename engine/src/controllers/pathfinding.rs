@@ -0,0 +1,142 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use actions::movement;
+use models::coordinate::Coordinate;
+use models::direction::Direction;
+use models::entity::EntityType;
+use models::view::WorldView;
+
+/// Directions an entity on `coord` can move to under the world's rules,
+/// without leaving the world, bumping into something or cutting corners.
+pub fn open_directions(view: &WorldView, coord: Coordinate) -> Vec<Direction> {
+    let rules = view.rules();
+    let topology = rules.topology();
+    let is_mountain = |c| {
+        view.entities().any(|entity| {
+            entity.occupies(c) && matches!(entity.entity_type, EntityType::Obstacle(_))
+        })
+    };
+
+    rules
+        .directions()
+        .iter()
+        .filter(|dir| is_open(view, topology.step(coord, **dir)))
+        .filter(|dir| {
+            rules.corner_cutting || !movement::cuts_corner(topology, coord, **dir, is_mountain)
+        })
+        .cloned()
+        .collect()
+}
+
+fn is_open(view: &WorldView, coord: Coordinate) -> bool {
+    view.is_inside(coord)
+        && view
+            .entities()
+            .all(|entity| !entity.occupies(coord) || entity.is_passable())
+}
+
+fn key(coord: Coordinate) -> (i32, i32) {
+    (coord.x as i32, coord.y as i32)
+}
+
+/// Breadth first search from `from` to the closest coordinate matching
+/// `is_goal`, returning the first step of the way.
+pub fn first_step<F>(view: &WorldView, from: Coordinate, is_goal: F) -> Option<Direction>
+where
+    F: Fn(Coordinate) -> bool,
+{
+    let topology = view.rules().topology();
+    let mut visited: BTreeSet<(i32, i32)> = BTreeSet::new();
+    let mut queue: VecDeque<(Coordinate, Direction)> = VecDeque::new();
+
+    visited.insert(key(from));
+    for dir in open_directions(view, from) {
+        let next = topology.step(from, dir);
+        visited.insert(key(next));
+        queue.push_back((next, dir));
+    }
+
+    while let Some((coord, first)) = queue.pop_front() {
+        if is_goal(coord) {
+            return Some(first);
+        }
+
+        for dir in open_directions(view, coord) {
+            let next = topology.step(coord, dir);
+            if visited.insert(key(next)) {
+                queue.push_back((next, first));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::actors::{gold, mountain};
+    use models::direction;
+    use models::rules::Rules;
+    use models::world::World;
+    use serializers::basic;
+
+    fn is_dir(dir: Direction, expected: Direction) -> bool {
+        Coordinate::new(dir.dx, dir.dy) == Coordinate::new(expected.dx, expected.dy)
+    }
+
+    #[test]
+    fn open_directions_in_a_corner() {
+        let mut world = World::create(Coordinate::new(2.0, 2.0));
+        world.register(mountain::create_at(Coordinate::new(1.0, 0.0)));
+        world.register(gold::create_at(Coordinate::new(0.0, 1.0)));
+
+        let view = WorldView::new(&world);
+        let open = open_directions(&view, Coordinate::new(0.0, 0.0));
+        assert_eq!(open.len(), 1);
+        assert!(is_dir(open[0], direction::DOWN));
+    }
+
+    #[test]
+    fn goes_around_walls() {
+        let world = basic::load(&["  #  ", "  #  ", "     "].join("\n"));
+        let view = WorldView::new(&world);
+        let goal = Coordinate::new(4.0, 0.0);
+
+        let step = first_step(&view, Coordinate::new(1.0, 0.0), |c| c == goal).unwrap();
+        assert!(is_dir(step, direction::DOWN));
+    }
+
+    #[test]
+    fn diagonal_shortcuts() {
+        let mut world = basic::load(&["     ", "     ", "     "].join("\n"));
+        world.set_rules(Rules::eight_way(false));
+        let view = WorldView::new(&world);
+        let goal = Coordinate::new(2.0, 2.0);
+
+        let step = first_step(&view, Coordinate::new(0.0, 0.0), |c| c == goal).unwrap();
+        assert_eq!(step, direction::DOWN_RIGHT);
+        assert_eq!(open_directions(&view, Coordinate::new(2.0, 1.0)).len(), 8);
+    }
+
+    #[test]
+    fn no_corner_cutting() {
+        let mut world = basic::load(&[" #", "  "].join("\n"));
+        world.set_rules(Rules::eight_way(false));
+        let open = open_directions(&WorldView::new(&world), Coordinate::new(0.0, 0.0));
+        assert_eq!(open, vec![direction::DOWN]);
+
+        world.set_rules(Rules::eight_way(true));
+        let open = open_directions(&WorldView::new(&world), Coordinate::new(0.0, 0.0));
+        assert_eq!(open, vec![direction::DOWN_RIGHT, direction::DOWN]);
+    }
+
+    #[test]
+    fn unreachable() {
+        let world = basic::load(&["  #  ", "  #  ", "  #  "].join("\n"));
+        let view = WorldView::new(&world);
+        let goal = Coordinate::new(4.0, 0.0);
+
+        assert!(first_step(&view, Coordinate::new(0.0, 0.0), |c| c == goal).is_none());
+    }
+}
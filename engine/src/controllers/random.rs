@@ -0,0 +1,58 @@
+use actions::action::ActionData;
+use controllers::controller::Controller;
+use models::rng::Rng;
+use models::view::WorldView;
+
+/// Wanders around, picking any legal move. It never shoots.
+pub struct Random {
+    rng: Rng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Controller for Random {
+    fn decide(&mut self, view: &WorldView, entity_id: i32) -> Option<ActionData> {
+        let moves: Vec<ActionData> = view
+            .legal_actions(entity_id)
+            .into_iter()
+            .filter(|action| action.direction().is_some())
+            .collect();
+        self.rng.choose(&moves).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::actors::player;
+    use models::coordinate::Coordinate;
+    use models::world::World;
+
+    #[test]
+    fn stays_inside_the_world() {
+        let mut world = World::create(Coordinate::new(1.0, 1.0));
+        let entity = world.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+        let mut random = Random::new(7);
+
+        for _ in 0..20 {
+            let action = random.decide(&WorldView::new(&world), entity.id).unwrap();
+            world.register_action(action);
+            assert!(::game::runner::run(&mut world).is_ok());
+        }
+    }
+
+    #[test]
+    fn stuck() {
+        let mut world = World::create(Coordinate::new(0.0, 0.0));
+        let entity = world.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+
+        let mut random = Random::new(7);
+        assert!(random.decide(&WorldView::new(&world), entity.id).is_none());
+    }
+}
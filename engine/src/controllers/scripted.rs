@@ -0,0 +1,48 @@
+use std::collections::VecDeque;
+
+use actions::action::ActionData;
+use actions::movement;
+use controllers::controller::Controller;
+use models::direction::Direction;
+use models::view::WorldView;
+
+/// Plays a fixed list of moves, `None` meaning wait, then waits forever.
+pub struct Scripted {
+    moves: VecDeque<Option<Direction>>,
+}
+
+impl Scripted {
+    pub fn new(moves: Vec<Option<Direction>>) -> Scripted {
+        Scripted {
+            moves: moves.into_iter().collect(),
+        }
+    }
+}
+
+impl Controller for Scripted {
+    fn decide(&mut self, _view: &WorldView, entity_id: i32) -> Option<ActionData> {
+        self.moves
+            .pop_front()
+            .and_then(|dir| dir)
+            .map(|dir| movement::towards(entity_id, dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::direction;
+    use models::world::World;
+
+    #[test]
+    fn plays_the_script() {
+        let world = World::new();
+        let view = WorldView::new(&world);
+        let mut scripted = Scripted::new(vec![Some(direction::UP), None, Some(direction::DOWN)]);
+
+        assert!(scripted.decide(&view, 1).is_some());
+        assert!(scripted.decide(&view, 1).is_none());
+        assert!(scripted.decide(&view, 1).is_some());
+        assert!(scripted.decide(&view, 1).is_none());
+    }
+}
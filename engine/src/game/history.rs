@@ -0,0 +1,67 @@
+use models::world::World;
+
+/// Checksums of the world after each processed turn.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct History {
+    checksums: Vec<u64>,
+}
+
+impl History {
+    pub fn new() -> History {
+        Default::default()
+    }
+
+    pub fn record(&mut self, world: &World) {
+        self.checksums.push(world.checksum());
+    }
+
+    pub fn checksums(&self) -> &[u64] {
+        &self.checksums
+    }
+
+    pub fn turns(&self) -> usize {
+        self.checksums.len()
+    }
+
+    /// Returns the first turn where both histories disagree, if any.
+    pub fn first_divergence(&self, other: &History) -> Option<usize> {
+        let divergence = self
+            .checksums
+            .iter()
+            .zip(other.checksums.iter())
+            .position(|(mine, theirs)| mine != theirs);
+
+        match divergence {
+            Some(turn) => Some(turn),
+            None if self.turns() != other.turns() => Some(self.turns().min(other.turns())),
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(checksums: &[u64]) -> History {
+        History {
+            checksums: checksums.to_vec(),
+        }
+    }
+
+    #[test]
+    fn first_divergence() {
+        assert_eq!(
+            history(&[1, 2, 3]).first_divergence(&history(&[1, 2, 3])),
+            None
+        );
+        assert_eq!(
+            history(&[1, 2, 3]).first_divergence(&history(&[1, 5, 3])),
+            Some(1)
+        );
+        assert_eq!(
+            history(&[1, 2]).first_divergence(&history(&[1, 2, 3])),
+            Some(2)
+        );
+    }
+}
@@ -1,3 +1,17 @@
+pub mod history;
+pub mod mode;
+pub mod puzzle;
 pub mod runner;
+pub mod scores;
+pub mod session;
+pub mod systems;
+pub mod tournament;
+pub mod victory;
 
+pub use self::history::History;
+pub use self::mode::GameMode;
+pub use self::puzzle::{Goal, Level, Puzzle};
 pub use self::runner::run;
+pub use self::scores::BestScores;
+pub use self::session::Session;
+pub use self::victory::{GameState, Victory};
@@ -0,0 +1,209 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use actions::action::ActionData;
+use actions::error::ActionError;
+use actions::handler::ActionHandlers;
+use actions::result::ActionResult;
+use actions::shooting;
+use game::victory::{self, GameState, Victory};
+use models::entity::EntityType;
+use models::world::World;
+
+/// The rules of a session on top of the engine's: which actions are allowed,
+/// how the game is won and how players are ranked. The runner calls the hooks
+/// as it processes a turn.
+pub trait GameMode: Send {
+    fn name(&self) -> &'static str;
+
+    /// Registers the actions the mode adds to the engine's, once it's played.
+    fn register_handlers(&self, _handlers: &mut ActionHandlers) {}
+
+    /// Called before the turn's actions are processed.
+    fn on_turn_start(&mut self, _world: &mut World) {}
+
+    /// Refuses actions the mode doesn't allow, on top of the engine's own
+    /// checks.
+    fn validate_action(&self, _world: &World, _action: &ActionData) -> ActionResult<()> {
+        Ok(())
+    }
+
+    /// Called after each action processed without error.
+    fn on_action_applied(&mut self, _world: &mut World, _action: &ActionData) {}
+
+    fn check_victory(&self, world: &World) -> GameState {
+        victory::state(world)
+    }
+
+    /// How well `player` is doing, higher being better.
+    fn score(&self, world: &World, player: i8) -> i32 {
+        gold(world, player)
+    }
+}
+
+/// Builds one of the modes by name, as used on command lines.
+pub fn by_name(name: &str) -> Option<Box<dyn GameMode>> {
+    match name {
+        "classic" => Some(Box::new(Classic)),
+        "race" => Some(Box::new(TradingRace::new(RACE_GOLD))),
+        "deathmatch" => Some(Box::new(Deathmatch::new())),
+        _ => None,
+    }
+}
+
+const RACE_GOLD: i32 = 20;
+
+fn gold(world: &World, player: i8) -> i32 {
+    world
+        .find_player(player)
+        .map_or(0, |entity| entity.inventory.gold)
+}
+
+fn standing(world: &World) -> BTreeSet<i8> {
+    world
+        .entities()
+        .filter_map(|entity| match entity.entity_type {
+            EntityType::Player(n) => Some(n),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Anything goes, and the game ends by the world's own victory conditions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Classic;
+
+impl GameMode for Classic {
+    fn name(&self) -> &'static str {
+        "classic"
+    }
+}
+
+/// No shooting: the first player carrying `goal` gold wins.
+#[derive(Clone, Copy, Debug)]
+pub struct TradingRace {
+    goal: i32,
+}
+
+impl TradingRace {
+    pub fn new(goal: i32) -> TradingRace {
+        TradingRace { goal }
+    }
+}
+
+impl GameMode for TradingRace {
+    fn name(&self) -> &'static str {
+        "race"
+    }
+
+    fn validate_action(&self, _world: &World, action: &ActionData) -> ActionResult<()> {
+        match action.kind {
+            shooting::SHOOT => Err(ActionError::Forbidden(action.entity_id)),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_victory(&self, world: &World) -> GameState {
+        Victory::Gold(self.goal)
+            .decide(world)
+            .unwrap_or(GameState::Running)
+    }
+}
+
+/// The last player standing wins, players scoring by the ones they shot.
+#[derive(Clone, Debug, Default)]
+pub struct Deathmatch {
+    standing: BTreeSet<i8>,
+    kills: BTreeMap<i8, i32>,
+}
+
+impl Deathmatch {
+    pub fn new() -> Deathmatch {
+        Default::default()
+    }
+}
+
+impl GameMode for Deathmatch {
+    fn name(&self) -> &'static str {
+        "deathmatch"
+    }
+
+    fn on_turn_start(&mut self, world: &mut World) {
+        self.standing = standing(world);
+    }
+
+    fn on_action_applied(&mut self, world: &mut World, action: &ActionData) {
+        let now = standing(world);
+        let killed = self.standing.difference(&now).count() as i32;
+        let shooter = world
+            .get_entity(action.entity_id)
+            .map(|entity| entity.entity_type);
+
+        if let (shooting::SHOOT, Some(EntityType::Player(n))) = (action.kind, shooter) {
+            *self.kills.entry(n).or_default() += killed;
+        }
+        self.standing = now;
+    }
+
+    fn check_victory(&self, world: &World) -> GameState {
+        Victory::LastStanding
+            .decide(world)
+            .unwrap_or(GameState::Running)
+    }
+
+    fn score(&self, _world: &World, player: i8) -> i32 {
+        self.kills.get(&player).cloned().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions::{movement, shooting};
+    use serializers::basic;
+
+    #[test]
+    fn by_name() {
+        assert_eq!(super::by_name("race").unwrap().name(), "race");
+        assert_eq!(super::by_name("deathmatch").unwrap().name(), "deathmatch");
+        assert!(super::by_name("tag").is_none());
+    }
+
+    #[test]
+    fn classic_uses_world_conditions() {
+        let mut world = basic::load("1  2");
+        assert_eq!(Classic.check_victory(&world), GameState::Running);
+
+        world.set_victory(vec![Victory::Richest(0)]);
+        assert_eq!(Classic.check_victory(&world), GameState::Draw);
+        assert_eq!(Classic.score(&world, 1), 0);
+    }
+
+    #[test]
+    fn race_forbids_shooting() {
+        let world = basic::load("1  2");
+        let race = TradingRace::new(10);
+
+        assert!(race.validate_action(&world, &movement::right(1)).is_ok());
+        assert_eq!(
+            race.validate_action(&world, &shooting::at(1, 2)),
+            Err(ActionError::Forbidden(1))
+        );
+        assert_eq!(race.check_victory(&world), GameState::Running);
+        assert_eq!(TradingRace::new(0).check_victory(&world), GameState::Draw);
+    }
+
+    #[test]
+    fn deathmatch_counts_kills() {
+        let mut world = basic::load("1  2");
+        let mut deathmatch = Deathmatch::new();
+        deathmatch.on_turn_start(&mut world);
+        assert_eq!(deathmatch.check_victory(&world), GameState::Running);
+
+        world.remove_entity(2);
+        deathmatch.on_action_applied(&mut world, &shooting::at(1, 2));
+
+        assert_eq!(deathmatch.score(&world, 1), 1);
+        assert_eq!(deathmatch.score(&world, 2), 0);
+        assert_eq!(deathmatch.check_victory(&world), GameState::Won(1));
+    }
+}
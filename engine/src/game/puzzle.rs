@@ -0,0 +1,193 @@
+use actions::result::ActionResult;
+use game::runner;
+use models::coordinate::Coordinate;
+use models::world::World;
+use serializers::basic;
+
+/// What solves a level.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Goal {
+    /// The player stands on the tile.
+    Reach(Coordinate),
+    /// Every tile holds a pushable.
+    Deliver(Vec<Coordinate>),
+    /// No treasure is left lying around.
+    CollectAll,
+}
+
+impl Goal {
+    pub fn is_met(&self, world: &World) -> bool {
+        let entities = || world.entities();
+        match self {
+            Goal::Reach(coord) => {
+                entities().any(|entity| entity.is_player() && entity.coord == *coord)
+            }
+            Goal::Deliver(targets) => targets.iter().all(|target| {
+                entities().any(|entity| entity.is_pushable() && entity.coord == *target)
+            }),
+            Goal::CollectAll => !entities().any(|entity| entity.is_treasure()),
+        }
+    }
+}
+
+/// A single player puzzle: a map, what solves it and the number of moves a
+/// good solution takes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    pub name: String,
+    pub goal: Goal,
+    pub par: u32,
+    /// The map, as read by `serializers::basic::load`.
+    pub map: String,
+}
+
+/// A level being played by player 1, counting the moves it makes.
+#[derive(Clone, Debug)]
+pub struct Puzzle {
+    level: Level,
+    player_id: Option<i32>,
+    position: Option<Coordinate>,
+    moves: u32,
+    solved: bool,
+}
+
+impl Puzzle {
+    /// Loads the level's map, returning the puzzle along with its world.
+    pub fn start(level: Level) -> (Puzzle, World) {
+        let world = basic::load(&level.map);
+        let player = world.find_player(1);
+
+        let puzzle = Puzzle {
+            solved: level.goal.is_met(&world),
+            level,
+            player_id: player.map(|player| player.id),
+            position: player.map(|player| player.coord),
+            moves: 0,
+        };
+        (puzzle, world)
+    }
+
+    pub fn level(&self) -> &Level {
+        &self.level
+    }
+
+    pub fn player_id(&self) -> Option<i32> {
+        self.player_id
+    }
+
+    pub fn moves(&self) -> u32 {
+        self.moves
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.solved
+    }
+
+    /// Solved in as many moves as par, or fewer.
+    pub fn is_under_par(&self) -> bool {
+        self.solved && self.moves <= self.level.par
+    }
+
+    /// Runs the turn's actions, then checks the goal.
+    pub fn run(&mut self, world: &mut World) -> ActionResult<()> {
+        let result = runner::run(world);
+        self.update(world);
+        result
+    }
+
+    /// Counts a move if the player changed tiles and checks the goal. Call
+    /// after every turn run elsewhere, such as by a `Session`. Nothing
+    /// counts once the level is solved.
+    pub fn update(&mut self, world: &World) {
+        if self.solved {
+            return;
+        }
+
+        let position = self
+            .player_id
+            .and_then(|id| world.get_entity(id))
+            .map(|player| player.coord);
+        if position.is_some() && position != self.position {
+            self.moves += 1;
+        }
+        self.position = position;
+        self.solved = self.level.goal.is_met(world);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions::action::ActionData;
+    use actions::movement;
+
+    fn level(goal: Goal, map: &[&str]) -> Level {
+        Level {
+            name: "Test".to_string(),
+            goal,
+            par: 2,
+            map: map.join("\n"),
+        }
+    }
+
+    fn step(puzzle: &mut Puzzle, world: &mut World, action: fn(i32) -> ActionData) {
+        world.register_action(action(puzzle.player_id().unwrap()));
+        let _ = puzzle.run(world);
+    }
+
+    #[test]
+    fn reach() {
+        let goal = Goal::Reach(Coordinate::new(2.0, 0.0));
+        let (mut puzzle, mut world) = Puzzle::start(level(goal, &["1  ", "   "]));
+        assert!(!puzzle.is_solved());
+
+        step(&mut puzzle, &mut world, movement::right);
+        assert!(!puzzle.is_solved());
+        step(&mut puzzle, &mut world, movement::right);
+        assert!(puzzle.is_solved());
+        assert!(puzzle.is_under_par());
+        assert_eq!(puzzle.moves(), 2);
+
+        step(&mut puzzle, &mut world, movement::down);
+        assert_eq!(puzzle.moves(), 2);
+    }
+
+    #[test]
+    fn bumps_do_not_count() {
+        let goal = Goal::Reach(Coordinate::new(0.0, 1.0));
+        let (mut puzzle, mut world) = Puzzle::start(level(goal, &["1# ", "   "]));
+
+        step(&mut puzzle, &mut world, movement::right);
+        step(&mut puzzle, &mut world, movement::up);
+        assert_eq!(puzzle.moves(), 0);
+
+        step(&mut puzzle, &mut world, movement::down);
+        assert_eq!(puzzle.moves(), 1);
+        assert!(puzzle.is_solved());
+    }
+
+    #[test]
+    fn deliver() {
+        let goal = Goal::Deliver(vec![Coordinate::new(3.0, 0.0)]);
+        let (mut puzzle, mut world) = Puzzle::start(level(goal, &["1o  "]));
+
+        step(&mut puzzle, &mut world, movement::right);
+        assert!(!puzzle.is_solved());
+        step(&mut puzzle, &mut world, movement::right);
+        assert!(puzzle.is_solved());
+    }
+
+    #[test]
+    fn collect_all() {
+        let (mut puzzle, mut world) = Puzzle::start(level(Goal::CollectAll, &["$1 $"]));
+
+        step(&mut puzzle, &mut world, movement::left);
+        step(&mut puzzle, &mut world, movement::right);
+        step(&mut puzzle, &mut world, movement::right);
+        assert!(!puzzle.is_solved());
+        step(&mut puzzle, &mut world, movement::right);
+        assert!(puzzle.is_solved());
+        assert_eq!(puzzle.moves(), 4);
+        assert!(!puzzle.is_under_par());
+    }
+}
@@ -1,22 +1,107 @@
 use actions::action::ActionData;
+use actions::error::ActionError;
 use actions::processor;
 use actions::result::ActionResult;
+use game::history::History;
+use game::mode::{Classic, GameMode};
+use game::systems;
+use game::victory::GameState;
 use models::world::World;
 
+/// Processes the registered actions as one turn, playing by the world's own
+/// victory conditions.
 pub fn run(world: &mut World) -> ActionResult<()> {
+    run_with(world, &mut Classic)
+}
+
+/// Processes the registered actions as one turn, letting `mode` refuse them
+/// and decide when the game is over. Once it is actions are dropped instead.
+/// Every action is processed even if others fail, the first error being
+/// returned.
+pub fn run_with(world: &mut World, mode: &mut dyn GameMode) -> ActionResult<()> {
+    run_turn(world, mode)?.into_iter().collect()
+}
+
+/// Same as `run_with`, returning the outcome of each action in the order
+/// they were registered: one refused action doesn't cancel the others. Once
+/// an action decides the game the ones after it fail with `GameOver`.
+pub fn run_turn(world: &mut World, mode: &mut dyn GameMode) -> ActionResult<Vec<ActionResult<()>>> {
+    if mode.check_victory(world) != GameState::Running {
+        world.clear_actions();
+        return Err(ActionError::GameOver);
+    }
     if !world.has_actions() {
-        return Ok(());
+        return Ok(vec![]);
     }
 
-    let result = run_actions(world);
+    mode.on_turn_start(world);
+    let outcomes = get_actions(world)
+        .into_iter()
+        .map(|action| match mode.check_victory(world) {
+            GameState::Running => run_action(world, mode, action),
+            _ => Err(ActionError::GameOver),
+        })
+        .collect();
     world.clear_actions();
+    end_turn(world);
 
-    result
+    Ok(outcomes)
 }
 
-fn run_actions(world: &mut World) -> ActionResult<()> {
-    let actions = get_actions(world);
-    processor::process_actions(world, &actions)
+/// Passes a turn nobody acted in, recording it like any other: the world
+/// keeps turning and its checksum follows `world.turn()`.
+pub fn pass_turn(
+    world: &mut World,
+    mode: &mut dyn GameMode,
+    history: &mut History,
+) -> ActionResult<()> {
+    if mode.check_victory(world) != GameState::Running {
+        return Err(ActionError::GameOver);
+    }
+
+    mode.on_turn_start(world);
+    end_turn(world);
+    history.record(world);
+
+    Ok(())
+}
+
+/// Whether the game is still on, as decided by the world's victory
+/// conditions.
+pub fn state(world: &World) -> GameState {
+    Classic.check_victory(world)
+}
+
+/// Same as `run_turn`, recording the world checksum after the turn.
+pub fn run_recorded(
+    world: &mut World,
+    mode: &mut dyn GameMode,
+    history: &mut History,
+) -> ActionResult<Vec<ActionResult<()>>> {
+    if !world.has_actions() {
+        return Ok(vec![]);
+    }
+
+    let outcomes = run_turn(world, mode);
+    if outcomes != Err(ActionError::GameOver) {
+        history.record(world);
+    }
+
+    outcomes
+}
+
+fn run_action(world: &mut World, mode: &mut dyn GameMode, action: ActionData) -> ActionResult<()> {
+    mode.validate_action(world, &action)?;
+    processor::process_action(world, action)?;
+    systems::after_action(world);
+    mode.on_action_applied(world, &action);
+
+    Ok(())
+}
+
+fn end_turn(world: &mut World) {
+    world.end_turn();
+    systems::after_turn(world);
 }
 
 fn get_actions(world: &World) -> Vec<ActionData> {
@@ -26,9 +111,12 @@ fn get_actions(world: &World) -> Vec<ActionData> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use actions::movement;
+    use actions::{movement, shooting};
+    use game::mode::TradingRace;
+    use game::victory::Victory;
     use models::coordinate::Coordinate;
     use models::entity::Entity;
+    use serializers::basic;
 
     #[test]
     fn run_actions() {
@@ -52,4 +140,152 @@ mod tests {
         assert!(entity.coord.is_at_x(1.0));
         assert!(entity.coord.is_at_y(1.0));
     }
+
+    #[test]
+    fn stops_once_the_game_is_over() {
+        let mut world = basic::load("1 2");
+        world.set_victory(vec![Victory::Reach(Coordinate::new(1.0, 0.0))]);
+        let mut history = History::new();
+
+        world.register_action(movement::right(1));
+        assert!(run_recorded(&mut world, &mut Classic, &mut history).is_ok());
+        assert_eq!(state(&world), GameState::Won(1));
+        assert_eq!(world.turn(), 1);
+
+        world.register_action(movement::left(2));
+        assert_eq!(
+            run_recorded(&mut world, &mut Classic, &mut history),
+            Err(ActionError::GameOver)
+        );
+        assert!(!world.has_actions());
+        assert_eq!(world.turn(), 1);
+        assert_eq!(history.turns(), 1);
+        assert_eq!(
+            world.get_entity(2).unwrap().coord,
+            Coordinate::new(2.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn actions_fail_on_their_own() {
+        let mut world = basic::load(&["1  2", "    "].join("\n"));
+
+        world.register_action(movement::up(1));
+        world.register_action(movement::down(1));
+        world.register_action(movement::left(2));
+        let outcomes = run_turn(&mut world, &mut Classic).unwrap();
+
+        assert_eq!(
+            outcomes,
+            vec![
+                Err(ActionError::OutOfMapCoordinate(0.0, -1.0)),
+                Ok(()),
+                Ok(())
+            ]
+        );
+        assert_eq!(
+            world.get_entity(1).unwrap().coord,
+            Coordinate::new(0.0, 1.0)
+        );
+        assert_eq!(
+            world.get_entity(2).unwrap().coord,
+            Coordinate::new(2.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn actions_after_the_winning_one_are_dropped() {
+        let mut world = basic::load("1 2");
+        world.set_victory(vec![Victory::Reach(Coordinate::new(1.0, 0.0))]);
+
+        world.register_action(movement::right(1));
+        world.register_action(movement::left(2));
+        assert_eq!(
+            run_turn(&mut world, &mut Classic),
+            Ok(vec![Ok(()), Err(ActionError::GameOver)])
+        );
+        assert_eq!(state(&world), GameState::Won(1));
+        assert_eq!(
+            world.get_entity(2).unwrap().coord,
+            Coordinate::new(2.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn mode_refuses_actions() {
+        let mut world = basic::load("1$ 2");
+        let mut race = TradingRace::new(10);
+
+        world.register_action(shooting::at(1, 3));
+        world.register_action(movement::right(1));
+        assert_eq!(
+            run_with(&mut world, &mut race),
+            Err(ActionError::Forbidden(1))
+        );
+        assert_eq!(world.turn(), 1);
+        assert_eq!(
+            world.get_entity(1).unwrap().coord,
+            Coordinate::new(1.0, 0.0)
+        );
+        assert_eq!(race.check_victory(&world), GameState::Won(1));
+        assert_eq!(state(&world), GameState::Running);
+    }
+
+    fn play_scripted_game() -> (History, String) {
+        let mut world = basic::load(&["1  #  2", "   B   ", "~~     "].join("\n"));
+        let mut history = History::new();
+        let script = [
+            vec![movement::right(1), movement::left(2)],
+            vec![movement::right(1), movement::left(2)],
+            vec![movement::down(1), movement::down(4)],
+            vec![movement::left(1), movement::left(1), movement::down(2)],
+            vec![movement::down(2), movement::left(2), movement::up(4)],
+        ];
+
+        for actions in script.iter() {
+            for action in actions {
+                world.register_action(*action);
+            }
+            let _ = run_recorded(&mut world, &mut Classic, &mut history);
+        }
+
+        (history, basic::print(&world))
+    }
+
+    #[test]
+    fn scripted_games_are_deterministic() {
+        let (history, board) = play_scripted_game();
+        let (other_history, other_board) = play_scripted_game();
+
+        assert_eq!(history.turns(), 5);
+        assert_eq!(history.first_divergence(&other_history), None);
+        assert_eq!(board, other_board);
+    }
+
+    #[test]
+    fn record_checksums() {
+        let mut history = History::new();
+        let mut world: World = World::new();
+        let entity = world.register(Entity::new(0, Coordinate::new(0.0, 0.0)));
+
+        assert!(run_recorded(&mut world, &mut Classic, &mut history).is_ok());
+        assert_eq!(history.turns(), 0);
+
+        world.register_action(movement::down(entity.id));
+        assert!(run_recorded(&mut world, &mut Classic, &mut history).is_ok());
+
+        world.register_action(movement::up(entity.id));
+        world.register_action(movement::up(entity.id));
+        assert_eq!(
+            run_recorded(&mut world, &mut Classic, &mut history),
+            Ok(vec![
+                Ok(()),
+                Err(ActionError::OutOfMapCoordinate(0.0, -1.0))
+            ])
+        );
+
+        assert_eq!(history.turns(), 2);
+        assert_eq!(history.checksums()[1], world.checksum());
+        assert_ne!(history.checksums()[0], history.checksums()[1]);
+    }
 }
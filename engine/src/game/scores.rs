@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Fewest moves each level was solved in, stored one `<moves> <level name>`
+/// line per level.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BestScores {
+    moves: BTreeMap<String, u32>,
+}
+
+impl BestScores {
+    pub fn new() -> BestScores {
+        Default::default()
+    }
+
+    /// Reads the scores saved at `path`, starting afresh if there are none
+    /// yet. Unreadable lines are skipped.
+    pub fn load(path: &Path) -> io::Result<BestScores> {
+        match fs::read_to_string(path) {
+            Ok(raw) => Ok(BestScores::parse(&raw)),
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => Ok(BestScores::new()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.print())
+    }
+
+    pub fn parse(raw: &str) -> BestScores {
+        let moves = raw
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(2, ' ');
+                let moves = parts.next()?.parse().ok()?;
+                Some((parts.next()?.to_string(), moves))
+            })
+            .collect();
+
+        BestScores { moves }
+    }
+
+    pub fn print(&self) -> String {
+        self.moves
+            .iter()
+            .map(|(level, moves)| format!("{} {}\n", moves, level))
+            .collect()
+    }
+
+    pub fn best(&self, level: &str) -> Option<u32> {
+        self.moves.get(level).cloned()
+    }
+
+    /// Keeps `moves` if it beats the best score for `level`, returning
+    /// whether it did.
+    pub fn record(&mut self, level: &str, moves: u32) -> bool {
+        if self.best(level).is_some_and(|best| best <= moves) {
+            return false;
+        }
+
+        self.moves.insert(level.to_string(), moves);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn record() {
+        let mut scores = BestScores::new();
+        assert!(scores.record("First steps", 12));
+        assert!(!scores.record("First steps", 14));
+        assert!(!scores.record("First steps", 12));
+        assert!(scores.record("First steps", 9));
+
+        assert_eq!(scores.best("First steps"), Some(9));
+        assert_eq!(scores.best("Second steps"), None);
+    }
+
+    #[test]
+    fn parse_what_is_printed() {
+        let mut scores = BestScores::new();
+        scores.record("First steps", 12);
+        scores.record("The long way", 40);
+
+        assert_eq!(scores.print(), "12 First steps\n40 The long way\n");
+        assert_eq!(BestScores::parse(&scores.print()), scores);
+        assert_eq!(BestScores::parse("garbage\n7 Fine\n").best("Fine"), Some(7));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = env::temp_dir().join(format!("wwtrader-scores-{}", std::process::id()));
+        assert_eq!(BestScores::load(&path).unwrap(), BestScores::new());
+
+        let mut scores = BestScores::new();
+        scores.record("First steps", 12);
+        scores.save(&path).unwrap();
+        let loaded = BestScores::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap(), scores);
+    }
+}
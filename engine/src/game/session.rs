@@ -0,0 +1,364 @@
+use actions::action::ActionData;
+use actions::error::ActionError;
+use actions::result::ActionResult;
+use controllers::{self, Controller};
+use game::history::History;
+use game::mode::{Classic, GameMode};
+use game::runner;
+use game::victory::GameState;
+use models::prefabs::Prefabs;
+use models::view::WorldView;
+use models::world::World;
+
+struct Seat {
+    entity_id: i32,
+    controller: Box<dyn Controller>,
+}
+
+/// A game being played: the world plus who controls which entity.
+pub struct Session {
+    pub world: World,
+    seats: Vec<Seat>,
+    history: History,
+    mode: Box<dyn GameMode>,
+}
+
+impl Session {
+    pub fn new(mut world: World) -> Session {
+        world.update_visibility();
+        Session {
+            world,
+            seats: vec![],
+            history: History::new(),
+            mode: Box::new(Classic),
+        }
+    }
+
+    /// Hands `entity_id` to `controller`. Controllers are asked in the order
+    /// they were seated, the first one asked moving on to the next seat every
+    /// turn.
+    pub fn seat(&mut self, entity_id: i32, mut controller: Box<dyn Controller>) {
+        controller.play_mode(self.mode.name());
+        self.seats.push(Seat {
+            entity_id,
+            controller,
+        });
+    }
+
+    /// Seats a bot for every actor whose prefab names one, players aside.
+    pub fn seat_actors(&mut self, prefabs: &Prefabs, seed: u64) {
+        let bots: Vec<(i32, Box<dyn Controller>)> = self
+            .world
+            .entities()
+            .filter(|entity| !entity.is_player())
+            .filter(|entity| self.seats.iter().all(|seat| seat.entity_id != entity.id))
+            .filter_map(|entity| {
+                let ai = prefabs.of(&entity)?.ai.as_ref()?;
+                let bot = controllers::by_name(ai, seed.wrapping_add(entity.id as u64))?;
+                Some((entity.id, bot))
+            })
+            .collect();
+
+        for (entity_id, bot) in bots {
+            self.seat(entity_id, bot);
+        }
+    }
+
+    /// Plays by `mode` instead of the world's own victory conditions, with
+    /// the actions it registers.
+    pub fn set_mode(&mut self, mode: Box<dyn GameMode>) {
+        mode.register_handlers(self.world.handlers_mut());
+        for seat in &mut self.seats {
+            seat.controller.play_mode(mode.name());
+        }
+        self.mode = mode;
+    }
+
+    pub fn mode(&self) -> &dyn GameMode {
+        self.mode.as_ref()
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn state(&self) -> GameState {
+        self.mode.check_victory(&self.world)
+    }
+
+    pub fn score(&self, player: i8) -> i32 {
+        self.mode.score(&self.world, player)
+    }
+
+    /// Asks every controller for its action and runs the turn. Controllers
+    /// only see what the entity's owner sees, and actions are issued on its
+    /// behalf. Each action is processed on its own, the errors of those
+    /// refused being returned; once the game is over the turn isn't played.
+    pub fn play_turn(&mut self) -> ActionResult<Vec<ActionError>> {
+        let actions = self.decide();
+        if actions.is_empty() {
+            // Everyone waited, the turn passes all the same
+            return match runner::pass_turn(&mut self.world, self.mode.as_mut(), &mut self.history) {
+                Err(ActionError::GameOver) | Ok(()) => Ok(vec![]),
+                Err(error) => Err(error),
+            };
+        }
+
+        for action in actions {
+            self.world.register_action(action);
+        }
+        let outcomes =
+            runner::run_recorded(&mut self.world, self.mode.as_mut(), &mut self.history)?;
+
+        Ok(outcomes.into_iter().filter_map(Result::err).collect())
+    }
+
+    fn decide(&mut self) -> Vec<ActionData> {
+        let world = &self.world;
+        let first = match self.seats.len() {
+            0 => 0,
+            seats => world.turn() as usize % seats,
+        };
+        let (before, after) = self.seats.split_at_mut(first);
+
+        after
+            .iter_mut()
+            .chain(before.iter_mut())
+            .filter_map(|seat| {
+                let entity = world.get_entity(seat.entity_id)?;
+                let view = WorldView::for_owner(world, entity.owner);
+                let action = seat.controller.decide(&view, entity.id)?;
+                Some(action.with_issuer(entity.owner))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions::action::{ActionKind, Payload};
+    use actions::error::ActionError;
+    use actions::handler::{ActionHandler, ActionHandlers};
+    use actions::movement;
+    use actions::result::ActionResult;
+    use controllers::{Greedy, Scripted};
+    use game::mode::TradingRace;
+    use models::coordinate::Coordinate;
+    use models::direction;
+    use models::entity::EntityType;
+    use models::prefabs::Prefab;
+    use serializers::basic;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct Hijacker {}
+
+    /// Waits, noting down when it was asked.
+    struct Witness {
+        asked: Rc<RefCell<Vec<i32>>>,
+    }
+
+    impl Controller for Witness {
+        fn decide(&mut self, _view: &WorldView, entity_id: i32) -> Option<ActionData> {
+            self.asked.borrow_mut().push(entity_id);
+            None
+        }
+    }
+
+    impl Controller for Hijacker {
+        fn decide(&mut self, _view: &WorldView, _entity_id: i32) -> Option<ActionData> {
+            Some(movement::down(2))
+        }
+    }
+
+    const HOLSTER: ActionKind = ActionKind("holster");
+
+    /// Puts the gun away, dropping its rounds.
+    struct Holster;
+
+    impl ActionHandler for Holster {
+        fn kind(&self) -> ActionKind {
+            HOLSTER
+        }
+
+        fn validate(&self, world: &World, action: &ActionData) -> ActionResult<()> {
+            if world.components().inventories.contains(action.entity_id) {
+                Ok(())
+            } else {
+                Err(ActionError::InvalidEntityId(action.entity_id))
+            }
+        }
+
+        fn process(&self, world: &mut World, action: &ActionData) -> ActionResult<()> {
+            self.validate(world, action)?;
+            let inventories = &mut world.components_mut().inventories;
+            if let Some(inventory) = inventories.get_mut(action.entity_id) {
+                inventory.ammo = 0;
+            }
+            Ok(())
+        }
+    }
+
+    /// A game where guns are put away.
+    struct Truce;
+
+    impl GameMode for Truce {
+        fn name(&self) -> &'static str {
+            "truce"
+        }
+
+        fn register_handlers(&self, handlers: &mut ActionHandlers) {
+            handlers.register(Holster);
+        }
+    }
+
+    struct Holsterer;
+
+    impl Controller for Holsterer {
+        fn decide(&mut self, _view: &WorldView, entity_id: i32) -> Option<ActionData> {
+            Some(ActionData::new(entity_id, HOLSTER, Payload::None))
+        }
+    }
+
+    #[test]
+    fn asks_every_controller() {
+        let mut session = Session::new(basic::load(&["1  2", "    "].join("\n")));
+        session.seat(1, Box::new(Scripted::new(vec![Some(direction::RIGHT)])));
+        session.seat(
+            2,
+            Box::new(Scripted::new(vec![None, Some(direction::DOWN)])),
+        );
+
+        assert_eq!(session.play_turn(), Ok(vec![]));
+        assert_eq!(session.play_turn(), Ok(vec![]));
+        assert_eq!(session.play_turn(), Ok(vec![]));
+
+        assert_eq!(session.world.turn(), 3);
+        assert_eq!(session.history().turns(), 3);
+        assert_eq!(
+            session.world.get_entity(1).unwrap().coord,
+            Coordinate::new(1.0, 0.0)
+        );
+        assert_eq!(
+            session.world.get_entity(2).unwrap().coord,
+            Coordinate::new(3.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn waiting_turns_are_recorded() {
+        let mut session = Session::new(basic::load("1 2"));
+        let asked = Rc::new(RefCell::new(vec![]));
+        session.seat(1, Box::new(Witness { asked }));
+
+        let before = session.world.checksum();
+        assert_eq!(session.play_turn(), Ok(vec![]));
+        assert_eq!(session.play_turn(), Ok(vec![]));
+
+        assert_eq!(session.world.turn(), 2);
+        assert_eq!(session.history().turns(), 2);
+        assert_eq!(session.history().checksums()[1], session.world.checksum());
+        assert_ne!(session.history().checksums()[0], before);
+    }
+
+    #[test]
+    fn seats_take_turns_going_first() {
+        let mut session = Session::new(basic::load("1 2 3"));
+        let asked = Rc::new(RefCell::new(vec![]));
+        for entity_id in 1..=3 {
+            let asked = asked.clone();
+            session.seat(entity_id, Box::new(Witness { asked }));
+        }
+
+        for _ in 0..4 {
+            assert_eq!(session.play_turn(), Ok(vec![]));
+        }
+
+        assert_eq!(*asked.borrow(), vec![1, 2, 3, 2, 3, 1, 3, 1, 2, 1, 2, 3]);
+    }
+
+    #[test]
+    fn actors_played_by_their_prefab_ai() {
+        let mut prefabs = Prefabs::builtin().clone();
+        prefabs.register(Prefab {
+            ai: Some("greedy".to_string()),
+            ..Prefab::new("sheriff", EntityType::Enemy('S'))
+        });
+        let mut session = Session::new(basic::load_with("1 S  $B", &prefabs));
+        session.seat_actors(&prefabs, 0);
+
+        for _ in 0..3 {
+            assert_eq!(session.play_turn(), Ok(vec![]));
+        }
+
+        assert_eq!(session.world.get_entity(2).unwrap().inventory.gold, 10);
+        assert_eq!(
+            session.world.get_entity(4).unwrap().coord,
+            Coordinate::new(6.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn controllers_only_command_their_entity() {
+        let mut session = Session::new(basic::load(&["1  2", "    "].join("\n")));
+        session.seat(1, Box::new(Hijacker {}));
+
+        assert_eq!(session.play_turn(), Ok(vec![ActionError::NotOwner(2)]));
+    }
+
+    #[test]
+    fn bad_controllers_dont_cancel_other_seats() {
+        let mut session = Session::new(basic::load(&["1  2", "    "].join("\n")));
+        session.seat(1, Box::new(Hijacker {}));
+        session.seat(2, Box::new(Scripted::new(vec![Some(direction::LEFT)])));
+
+        assert_eq!(session.play_turn(), Ok(vec![ActionError::NotOwner(2)]));
+        assert_eq!(
+            session.world.get_entity(2).unwrap().coord,
+            Coordinate::new(2.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn bots_against_each_other() {
+        let mut session = Session::new(basic::load(&["1  $  $ 2", "   #     "].join("\n")));
+        session.seat(1, Box::new(Greedy::new()));
+        session.seat(4, Box::new(Greedy::new()));
+
+        for _ in 0..10 {
+            assert_eq!(session.play_turn(), Ok(vec![]));
+        }
+
+        assert_eq!(session.world.get_entity(1).unwrap().inventory.gold, 10);
+        assert_eq!(session.world.get_entity(4).unwrap().inventory.gold, 10);
+    }
+
+    #[test]
+    fn modes_register_their_actions() {
+        let mut session = Session::new(basic::load("1 2"));
+        session.seat(1, Box::new(Holsterer));
+        assert_eq!(
+            session.play_turn(),
+            Ok(vec![ActionError::UnknownAction("holster")])
+        );
+
+        session.set_mode(Box::new(Truce));
+        assert_eq!(session.play_turn(), Ok(vec![]));
+        assert_eq!(session.world.get_entity(1).unwrap().inventory.ammo, 0);
+    }
+
+    #[test]
+    fn plays_by_its_mode() {
+        let mut session = Session::new(basic::load("1$ 2"));
+        let moves = vec![Some(direction::RIGHT), Some(direction::RIGHT)];
+        session.seat(1, Box::new(Scripted::new(moves)));
+        session.set_mode(Box::new(TradingRace::new(10)));
+
+        assert_eq!(session.play_turn(), Ok(vec![]));
+        assert_eq!(session.mode().name(), "race");
+        assert_eq!(session.state(), GameState::Won(1));
+        assert_eq!(session.score(1), 10);
+        assert_eq!(session.play_turn(), Err(ActionError::GameOver));
+    }
+}
@@ -0,0 +1,81 @@
+use models::actors::gold;
+use models::inventory::Inventory;
+use models::world::World;
+
+/// Systems run after every processed action.
+pub fn after_action(world: &mut World) {
+    deaths(world);
+}
+
+/// Systems run once the turn's actions are all processed.
+pub fn after_turn(world: &mut World) {
+    visibility(world);
+}
+
+/// Removes the entities out of health, leaving the gold they carried on
+/// their tile.
+pub fn deaths(world: &mut World) {
+    let dead: Vec<i32> = world
+        .components()
+        .healths
+        .iter()
+        .filter(|(_, health)| health.is_dead())
+        .map(|(id, _)| id)
+        .collect();
+
+    for id in dead {
+        let components = world.components_mut();
+        let carried = components.inventories.get(id).map_or(0, |inv| inv.gold);
+        let position = components.positions.get(id).cloned();
+        components.remove(id);
+
+        if let (true, Some(position)) = (carried > 0, position) {
+            let loot = Inventory::with_gold(carried);
+            world.register(gold::create_at(position.coord).with_inventory(loot));
+        }
+    }
+}
+
+/// Recomputes what every player sees from the entities it owns.
+pub fn visibility(world: &mut World) {
+    world.update_visibility();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::components::Health;
+    use serializers::basic;
+
+    fn wound(world: &mut World, id: i32, health: Health) {
+        world.components_mut().healths.insert(id, health);
+    }
+
+    #[test]
+    fn deaths_remove_entities_out_of_health() {
+        let mut world = basic::load("1 B 2");
+        wound(&mut world, 2, Health::new(1).damaged(1));
+        wound(&mut world, 3, Health::new(2).damaged(1));
+        deaths(&mut world);
+
+        assert!(world.get_entity(2).is_none());
+        assert_eq!(
+            world.get_entity(3).unwrap().health,
+            Some(Health::new(2).damaged(1))
+        );
+        assert_eq!(world.entity_count(), 2);
+    }
+
+    #[test]
+    fn deaths_drop_gold() {
+        let mut world = basic::load("1  2");
+        let target = world.get_entity(2).unwrap();
+        world.update_entity(target.with_inventory(Inventory::with_gold(30)));
+        wound(&mut world, 2, Health::new(1).damaged(1));
+        after_action(&mut world);
+
+        let loot = world.on_coord(target.coord).unwrap();
+        assert!(loot.is_treasure());
+        assert_eq!(loot.inventory.gold, 30);
+    }
+}
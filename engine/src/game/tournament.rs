@@ -0,0 +1,395 @@
+use std::fmt;
+
+use actions::error::ActionError;
+use controllers;
+use game::mode;
+use game::session::Session;
+use game::victory::GameState;
+use models::entity::Entity;
+use models::world::World;
+use serializers::basic;
+
+/// An enum containing all kinds of tournament errors.
+#[derive(Debug, PartialEq)]
+pub enum TournamentError {
+    UnknownController(String),
+    UnknownMode(String),
+    MissingPlayer(i8),
+    /// A turn of the match couldn't be played at all.
+    Turn(ActionError),
+}
+
+impl fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TournamentError::UnknownController(name) => write!(f, "Unknown controller: {}", name),
+            TournamentError::UnknownMode(name) => write!(f, "Unknown game mode: {}", name),
+            TournamentError::MissingPlayer(n) => write!(f, "Map has no player {}", n),
+            TournamentError::Turn(error) => write!(f, "Turn failed: {}", error),
+        }
+    }
+}
+
+pub type TournamentResult<T> = std::result::Result<T, TournamentError>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchResult {
+    pub seed: u64,
+    pub gold: [i32; 2],
+    /// As scored by the game mode.
+    pub score: [i32; 2],
+    pub turns: u32,
+    /// Actions refused during the match, like moves into walls.
+    pub refused: u32,
+    /// How the mode left the game, `Running` when the turn limit ended it.
+    pub state: GameState,
+}
+
+impl MatchResult {
+    /// Index of the player the mode declared the winner. Draws and matches
+    /// cut short by the turn limit go to the better score, `None` on a tie.
+    pub fn winner(&self) -> Option<usize> {
+        match self.state {
+            GameState::Won(1) => return Some(0),
+            GameState::Won(2) => return Some(1),
+            _ => {}
+        }
+
+        if self.score[0] > self.score[1] {
+            Some(0)
+        } else if self.score[1] > self.score[0] {
+            Some(1)
+        } else {
+            None
+        }
+    }
+}
+
+fn player_entity(world: &World, number: i8) -> TournamentResult<Entity> {
+    world
+        .find_player(number)
+        .ok_or(TournamentError::MissingPlayer(number))
+}
+
+/// Plays `controllers[0]` as player 1 against `controllers[1]` as player 2
+/// under the rules of `mode` until the mode ends the game or `turn_limit` is
+/// reached.
+pub fn play_match(
+    map: &str,
+    controllers: [&str; 2],
+    mode: &str,
+    seed: u64,
+    turn_limit: u32,
+) -> TournamentResult<MatchResult> {
+    let mut world = basic::load(map);
+    world.reseed(seed);
+
+    let players = [player_entity(&world, 1)?, player_entity(&world, 2)?];
+    let mut session = Session::new(world);
+    let mode = mode::by_name(mode).ok_or_else(|| TournamentError::UnknownMode(mode.to_string()))?;
+    session.set_mode(mode);
+    for (index, name) in controllers.iter().enumerate() {
+        let controller_seed = seed.wrapping_mul(2).wrapping_add(index as u64);
+        let controller = controllers::by_name(name, controller_seed)
+            .ok_or_else(|| TournamentError::UnknownController(name.to_string()))?;
+        session.seat(players[index].id, controller);
+    }
+
+    let mut refused = 0;
+    while session.world.turn() < turn_limit && session.state() == GameState::Running {
+        // Bumping into things just wastes the turn
+        let errors = session.play_turn().map_err(TournamentError::Turn)?;
+        refused += errors.len() as u32;
+    }
+
+    let gold = |entity: &Entity| {
+        session
+            .world
+            .get_entity(entity.id)
+            .map_or(0, |entity| entity.inventory.gold)
+    };
+
+    Ok(MatchResult {
+        seed,
+        gold: [gold(&players[0]), gold(&players[1])],
+        score: [session.score(1), session.score(2)],
+        turns: session.world.turn(),
+        refused,
+        state: session.state(),
+    })
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Standing {
+    pub controller: String,
+    pub matches: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub gold: i64,
+    pub turns: u64,
+}
+
+impl Standing {
+    fn new(controller: &str) -> Standing {
+        Standing {
+            controller: controller.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn record(&mut self, result: &MatchResult, index: usize) {
+        self.matches += 1;
+        self.gold += i64::from(result.gold[index]);
+        self.turns += u64::from(result.turns);
+        match result.winner() {
+            Some(winner) if winner == index => self.wins += 1,
+            Some(_) => self.losses += 1,
+            None => self.draws += 1,
+        }
+    }
+
+    pub fn average_gold(&self) -> f64 {
+        average(self.gold as f64, self.matches)
+    }
+
+    pub fn average_turns(&self) -> f64 {
+        average(self.turns as f64, self.matches)
+    }
+}
+
+fn average(total: f64, matches: u32) -> f64 {
+    if matches == 0 {
+        0.0
+    } else {
+        total / f64::from(matches)
+    }
+}
+
+/// Every controller plays every other one, on both sides, `matches` times on
+/// each map. Match seeds derive from `seed`, so a tournament is reproducible.
+pub struct Tournament {
+    pub maps: Vec<String>,
+    pub controllers: Vec<String>,
+    /// Name of the game mode every match is played under.
+    pub mode: String,
+    pub matches: u32,
+    pub turn_limit: u32,
+    pub seed: u64,
+}
+
+impl Tournament {
+    fn pairings(&self) -> Vec<(usize, usize)> {
+        let count = self.controllers.len();
+        if count == 1 {
+            return vec![(0, 0)];
+        }
+
+        let mut pairings = vec![];
+        for first in 0..count {
+            for second in 0..count {
+                if first != second {
+                    pairings.push((first, second));
+                }
+            }
+        }
+        pairings
+    }
+
+    pub fn play(&self) -> TournamentResult<Vec<Standing>> {
+        let mut standings: Vec<Standing> = self
+            .controllers
+            .iter()
+            .map(|name| Standing::new(name))
+            .collect();
+
+        let mut seed = self.seed;
+        for map in &self.maps {
+            for (first, second) in self.pairings() {
+                for _ in 0..self.matches {
+                    let names = [
+                        self.controllers[first].as_str(),
+                        self.controllers[second].as_str(),
+                    ];
+                    let result = play_match(map, names, &self.mode, seed, self.turn_limit)?;
+                    standings[first].record(&result, 0);
+                    standings[second].record(&result, 1);
+                    seed = seed.wrapping_add(1);
+                }
+            }
+        }
+
+        Ok(standings)
+    }
+}
+
+pub fn to_csv(standings: &[Standing]) -> String {
+    let mut lines =
+        vec!["controller,matches,wins,draws,losses,average_gold,average_turns".to_string()];
+    for standing in standings {
+        lines.push(format!(
+            "{},{},{},{},{},{:.2},{:.2}",
+            standing.controller,
+            standing.matches,
+            standing.wins,
+            standing.draws,
+            standing.losses,
+            standing.average_gold(),
+            standing.average_turns()
+        ));
+    }
+
+    lines.join("\n")
+}
+
+pub fn to_json(standings: &[Standing]) -> String {
+    let rows: Vec<String> = standings
+        .iter()
+        .map(|standing| {
+            format!(
+                "  {{\"controller\": \"{}\", \"matches\": {}, \"wins\": {}, \"draws\": {}, \"losses\": {}, \"average_gold\": {:.2}, \"average_turns\": {:.2}}}",
+                standing.controller.replace('\\', "\\\\").replace('"', "\\\""),
+                standing.matches,
+                standing.wins,
+                standing.draws,
+                standing.losses,
+                standing.average_gold(),
+                standing.average_turns()
+            )
+        })
+        .collect();
+
+    format!("[\n{}\n]", rows.join(",\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "1 $   $ \n  #  #  \n $   $ 2";
+
+    fn tournament() -> Tournament {
+        Tournament {
+            maps: vec![MAP.to_string()],
+            controllers: vec!["greedy".to_string(), "random".to_string()],
+            mode: "classic".to_string(),
+            matches: 3,
+            turn_limit: 30,
+            seed: 11,
+        }
+    }
+
+    #[test]
+    fn matches_are_reproducible() {
+        let first = play_match(MAP, ["random", "random"], "classic", 5, 30).unwrap();
+        let second = play_match(MAP, ["random", "random"], "classic", 5, 30).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn ends_by_the_map_victory() {
+        let map = format!("victory gold 20\n{}", MAP);
+        let result = play_match(&map, ["greedy", "greedy"], "classic", 1, 100).unwrap();
+        assert!(result.turns < 100);
+        assert!(result.gold.iter().any(|gold| *gold >= 20));
+    }
+
+    #[test]
+    fn winner_by_the_mode() {
+        // Player 2 grabs two piles while player 1 walks to the goal
+        let map = "victory reach 2 0\n1   $$$2";
+        let result = play_match(map, ["greedy", "greedy"], "classic", 1, 10).unwrap();
+        assert_eq!(result.state, GameState::Won(1));
+        assert!(result.gold[0] < result.gold[1]);
+        assert!(result.score[0] < result.score[1]);
+        assert_eq!(result.winner(), Some(0));
+    }
+
+    #[test]
+    fn plays_on_once_treasure_runs_out() {
+        let result = play_match(MAP, ["greedy", "greedy"], "classic", 1, 100).unwrap();
+        assert_eq!(result.turns, 100);
+        assert_eq!(result.gold[0] + result.gold[1], 40);
+    }
+
+    #[test]
+    fn counts_refused_actions() {
+        // Every move from the corner but one leads off the map
+        let result = play_match("1\n \n2", ["random", "random"], "race", 1, 20).unwrap();
+        assert!(result.refused > 0);
+    }
+
+    #[test]
+    fn enforces_turn_limit() {
+        let result = play_match(MAP, ["random", "random"], "classic", 1, 2).unwrap();
+        assert_eq!(result.turns, 2);
+    }
+
+    #[test]
+    fn unknown_controller() {
+        assert_eq!(
+            play_match(MAP, ["greedy", "psychic"], "classic", 1, 10).err(),
+            Some(TournamentError::UnknownController("psychic".to_string()))
+        );
+    }
+
+    #[test]
+    fn unknown_mode() {
+        assert_eq!(
+            play_match(MAP, ["greedy", "greedy"], "tag", 1, 10).err(),
+            Some(TournamentError::UnknownMode("tag".to_string()))
+        );
+    }
+
+    #[test]
+    fn scores_by_mode() {
+        let result = play_match("1 2", ["mcts", "greedy"], "deathmatch", 1, 50).unwrap();
+        assert!(result.turns < 50);
+        assert_eq!(result.score, [1, 0]);
+        assert_eq!(result.winner(), Some(0));
+    }
+
+    #[test]
+    fn missing_player() {
+        assert_eq!(
+            play_match("1 $", ["greedy", "greedy"], "classic", 1, 10).err(),
+            Some(TournamentError::MissingPlayer(2))
+        );
+    }
+
+    #[test]
+    fn greedy_beats_random() {
+        let standings = tournament().play().unwrap();
+        assert_eq!(standings, tournament().play().unwrap());
+
+        let greedy = &standings[0];
+        let random = &standings[1];
+        assert_eq!(greedy.matches, 6);
+        assert_eq!(greedy.wins + greedy.draws + greedy.losses, 6);
+        assert_eq!(greedy.wins, random.losses);
+        assert!(greedy.wins > greedy.losses);
+        assert!(greedy.average_gold() > random.average_gold());
+    }
+
+    #[test]
+    fn output() {
+        let standings = vec![Standing {
+            controller: "greedy".to_string(),
+            matches: 2,
+            wins: 1,
+            draws: 1,
+            losses: 0,
+            gold: 25,
+            turns: 9,
+        }];
+
+        assert_eq!(
+            to_csv(&standings),
+            "controller,matches,wins,draws,losses,average_gold,average_turns\ngreedy,2,1,1,0,12.50,4.50"
+        );
+        assert_eq!(
+            to_json(&standings),
+            "[\n  {\"controller\": \"greedy\", \"matches\": 2, \"wins\": 1, \"draws\": 1, \"losses\": 0, \"average_gold\": 12.50, \"average_turns\": 4.50}\n]"
+        );
+    }
+}
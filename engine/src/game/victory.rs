@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use models::coordinate::Coordinate;
+use models::entity::EntityType;
+use models::world::World;
+
+/// A way to end the game, checked after each processed action.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Victory {
+    /// First player carrying at least this much gold.
+    Gold(i32),
+    /// Last player still standing.
+    LastStanding,
+    /// Richest player once this many turns were processed.
+    Richest(u32),
+    /// First player standing on the tile.
+    Reach(Coordinate),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameState {
+    Running,
+    Won(i8),
+    Draw,
+}
+
+/// Gold carried by each player still in the game.
+fn players(world: &World) -> BTreeMap<i8, i32> {
+    world
+        .entities()
+        .filter_map(|entity| match entity.entity_type {
+            EntityType::Player(n) => Some((n, entity.inventory.gold)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The richest of `players`, or a draw on a tie.
+fn richest(players: &BTreeMap<i8, i32>) -> GameState {
+    let most = players.values().max();
+    let mut richest = players.iter().filter(|(_, gold)| Some(*gold) == most);
+
+    match (richest.next(), richest.next()) {
+        (Some((player, _)), None) => GameState::Won(*player),
+        _ => GameState::Draw,
+    }
+}
+
+impl Victory {
+    /// How the game ended by this condition, if it did.
+    pub fn decide(&self, world: &World) -> Option<GameState> {
+        let players = players(world);
+
+        match self {
+            Victory::Gold(goal) => {
+                let rich: BTreeMap<i8, i32> = players
+                    .into_iter()
+                    .filter(|(_, gold)| gold >= goal)
+                    .collect();
+                Some(richest(&rich)).filter(|_| !rich.is_empty())
+            }
+            Victory::LastStanding => match players.len() {
+                0 => Some(GameState::Draw),
+                1 => players.keys().next().map(|player| GameState::Won(*player)),
+                _ => None,
+            },
+            Victory::Richest(turns) if world.turn() >= *turns => Some(richest(&players)),
+            Victory::Richest(_) => None,
+            Victory::Reach(coord) => world
+                .entities()
+                .find_map(|entity| match entity.entity_type {
+                    EntityType::Player(n) if entity.coord == *coord => Some(GameState::Won(n)),
+                    _ => None,
+                }),
+        }
+    }
+}
+
+/// Where the game stands, going by the first of the world's victory
+/// conditions that's met. Without conditions the game runs forever.
+pub fn state(world: &World) -> GameState {
+    world
+        .victory()
+        .iter()
+        .find_map(|victory| victory.decide(world))
+        .unwrap_or(GameState::Running)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::actors::player;
+    use models::inventory::Inventory;
+    use serializers::basic;
+
+    fn decide(victory: Victory, map: &[&str]) -> Option<GameState> {
+        victory.decide(&basic::load(&map.join("\n")))
+    }
+
+    fn with_gold(world: &mut World, player: i8, gold: i32) {
+        let entity = world.find_player(player).unwrap();
+        world.update_entity(
+            entity.with_inventory(entity.inventory.merge(Inventory::with_gold(gold))),
+        );
+    }
+
+    #[test]
+    fn gold() {
+        let mut world = basic::load("1  2");
+        assert_eq!(Victory::Gold(10).decide(&world), None);
+
+        with_gold(&mut world, 2, 10);
+        assert_eq!(Victory::Gold(10).decide(&world), Some(GameState::Won(2)));
+
+        with_gold(&mut world, 1, 20);
+        assert_eq!(Victory::Gold(10).decide(&world), Some(GameState::Won(1)));
+
+        with_gold(&mut world, 2, 10);
+        assert_eq!(Victory::Gold(10).decide(&world), Some(GameState::Draw));
+    }
+
+    #[test]
+    fn last_standing() {
+        assert_eq!(decide(Victory::LastStanding, &["1  2"]), None);
+        assert_eq!(
+            decide(Victory::LastStanding, &["   2"]),
+            Some(GameState::Won(2))
+        );
+        assert_eq!(
+            decide(Victory::LastStanding, &["  B "]),
+            Some(GameState::Draw)
+        );
+    }
+
+    #[test]
+    fn richest() {
+        let mut world = basic::load("1  2");
+        with_gold(&mut world, 1, 5);
+        assert_eq!(Victory::Richest(2).decide(&world), None);
+
+        world.end_turn();
+        world.end_turn();
+        assert_eq!(Victory::Richest(2).decide(&world), Some(GameState::Won(1)));
+
+        with_gold(&mut world, 2, 5);
+        assert_eq!(Victory::Richest(2).decide(&world), Some(GameState::Draw));
+    }
+
+    #[test]
+    fn reach() {
+        let goal = Victory::Reach(Coordinate::new(1.0, 0.0));
+        assert_eq!(decide(goal, &["1  2"]), None);
+        assert_eq!(decide(goal, &[" 2 1"]), Some(GameState::Won(2)));
+    }
+
+    #[test]
+    fn first_condition_met_wins() {
+        let mut world = World::new();
+        assert_eq!(state(&world), GameState::Running);
+
+        world.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+        world.set_victory(vec![Victory::Gold(10), Victory::LastStanding]);
+        assert_eq!(state(&world), GameState::Won(1));
+
+        world.register(player::create_at(2, Coordinate::new(1.0, 0.0)));
+        assert_eq!(state(&world), GameState::Running);
+    }
+}
@@ -0,0 +1,75 @@
+use actions::action::ActionData;
+use actions::{movement, shooting};
+use models::direction::Direction;
+use models::world::World;
+
+pub const WAIT: usize = 0;
+
+/// The discrete actions of an environment: wait, then one move per
+/// direction the world's topology has, then one shot per agent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionSpace {
+    directions: &'static [Direction],
+    targets: Vec<i32>,
+}
+
+impl ActionSpace {
+    pub fn new(world: &World, agents: &[i32]) -> ActionSpace {
+        ActionSpace {
+            directions: world.rules().directions(),
+            targets: agents.to_vec(),
+        }
+    }
+
+    /// Number of discrete actions.
+    pub fn size(&self) -> usize {
+        1 + self.directions.len() + self.targets.len()
+    }
+
+    /// Maps a discrete action to what `entity_id` does. `WAIT` and out of
+    /// range indexes do nothing.
+    pub fn to_action(&self, index: usize, entity_id: i32) -> Option<ActionData> {
+        if index == WAIT {
+            return None;
+        }
+
+        let index = index - 1;
+        match self.directions.get(index) {
+            Some(dir) => Some(movement::towards(entity_id, *dir)),
+            None => self
+                .targets
+                .get(index - self.directions.len())
+                .map(|target| shooting::at(entity_id, *target)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::rules::Rules;
+
+    #[test]
+    fn maps_indexes() {
+        let space = ActionSpace::new(&World::new(), &[1, 4]);
+        assert_eq!(space.size(), 1 + 4 + 2);
+        assert!(space.to_action(WAIT, 1).is_none());
+        assert!(space.to_action(space.size(), 1).is_none());
+
+        for index in 1..5 {
+            let action = space.to_action(index, 7).unwrap();
+            assert_eq!(action.entity_id, 7);
+            assert!(action.direction().is_some());
+        }
+        assert_eq!(space.to_action(5, 7).unwrap().target(), Some(1));
+        assert_eq!(space.to_action(6, 7).unwrap().target(), Some(4));
+    }
+
+    #[test]
+    fn follows_the_topology() {
+        let space = ActionSpace::new(&World::with_rules(Rules::hex()), &[1]);
+        assert_eq!(space.size(), 1 + 6 + 1);
+        assert!(space.to_action(6, 1).unwrap().direction().is_some());
+        assert_eq!(space.to_action(7, 1).unwrap().target(), Some(1));
+    }
+}
@@ -0,0 +1,187 @@
+use game::runner;
+use game::victory::GameState;
+use gym::action_space::ActionSpace;
+use gym::observation::Observation;
+use models::entity::EntityType;
+use models::world::World;
+use serializers::basic;
+
+/// Reinforcement learning environment over a map. Every player on the map
+/// is an agent, ordered by player number; rewards are the gold each agent
+/// picked up during the step.
+pub struct Environment {
+    map: String,
+    turn_limit: u32,
+    world: World,
+    agents: Vec<i32>,
+    action_space: ActionSpace,
+}
+
+impl Environment {
+    pub fn new(map: &str, turn_limit: u32) -> Environment {
+        let world = World::new();
+        let mut environment = Environment {
+            map: map.to_string(),
+            turn_limit,
+            action_space: ActionSpace::new(&world, &[]),
+            world,
+            agents: vec![],
+        };
+        environment.reset(0);
+        environment
+    }
+
+    pub fn agents(&self) -> usize {
+        self.agents.len()
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// The discrete actions agents pick from, for the map's topology.
+    pub fn action_space(&self) -> &ActionSpace {
+        &self.action_space
+    }
+
+    /// Starts a new episode.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.world = basic::load(&self.map);
+        self.world.reseed(seed);
+
+        let mut players: Vec<(i8, i32)> = self
+            .world
+            .entities()
+            .filter_map(|entity| match entity.entity_type {
+                EntityType::Player(n) => Some((n, entity.id)),
+                _ => None,
+            })
+            .collect();
+        players.sort();
+        self.agents = players.into_iter().map(|(_, id)| id).collect();
+        self.action_space = ActionSpace::new(&self.world, &self.agents);
+
+        self.observe()
+    }
+
+    /// Plays one discrete action per agent as a turn, in agent order.
+    /// Illegal actions, like moves into walls, just waste the agent's turn.
+    pub fn step(&mut self, actions: &[usize]) -> (Observation, Vec<f32>, bool) {
+        let before = self.gold();
+
+        for (entity_id, index) in self.agents.iter().zip(actions.iter()) {
+            if let Some(action) = self.action_space.to_action(*index, *entity_id) {
+                self.world.register_action(action);
+            }
+        }
+        if self.world.has_actions() {
+            // Refused actions are wasted, the others are played all the same
+            let _ = runner::run(&mut self.world);
+        } else {
+            self.world.end_turn();
+        }
+
+        let rewards = self
+            .gold()
+            .iter()
+            .zip(before.iter())
+            .map(|(after, before)| (after - before) as f32)
+            .collect();
+
+        (self.observe(), rewards, self.is_done())
+    }
+
+    fn gold(&self) -> Vec<i32> {
+        self.agents
+            .iter()
+            .map(|id| self.world.get_entity(*id).map_or(0, |e| e.inventory.gold))
+            .collect()
+    }
+
+    fn is_done(&self) -> bool {
+        self.world.turn() >= self.turn_limit
+            || !self.world.entities().any(|e| e.is_treasure())
+            || runner::state(&self.world) != GameState::Running
+    }
+
+    fn observe(&self) -> Observation {
+        Observation::encode(&self.world, &self.agents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gym::action_space::WAIT;
+    use gym::observation::{PLAYER, TREASURE};
+    use models::rng::Rng;
+
+    const RIGHT: usize = 2;
+    const DOWN: usize = 3;
+
+    #[test]
+    fn agents_in_player_order() {
+        let environment = Environment::new("2 $ 1", 10);
+        assert_eq!(environment.agents(), 2);
+        assert_eq!(environment.agents, vec![3, 1]);
+    }
+
+    #[test]
+    fn collect_treasure() {
+        let mut environment = Environment::new(&["1$ ", "  2", " $ "].join("\n"), 10);
+        let observation = environment.reset(3);
+        assert_eq!(observation.at(TREASURE, 1, 0), 1.0);
+
+        let (observation, rewards, done) = environment.step(&[RIGHT, WAIT]);
+        assert_eq!(rewards, vec![10.0, 0.0]);
+        assert_eq!(environment.world().turn(), 1);
+        assert_eq!(observation.scalars, vec![10.0, 6.0, 0.0, 6.0]);
+        assert_eq!(observation.at(PLAYER, 1, 0), 1.0);
+        assert_eq!(observation.at(TREASURE, 1, 0), 0.0);
+        assert!(!done);
+
+        let (_, rewards, done) = environment.step(&[WAIT, DOWN]);
+        assert_eq!(rewards, vec![0.0, 0.0]);
+        assert_eq!(environment.world().turn(), 2);
+        assert!(!done);
+    }
+
+    #[test]
+    fn done_when_treasure_runs_out() {
+        let mut environment = Environment::new("1$ 2", 10);
+        let (_, _, done) = environment.step(&[RIGHT, WAIT]);
+        assert!(done);
+    }
+
+    #[test]
+    fn done_on_turn_limit() {
+        let mut environment = Environment::new("1 $ 2", 2);
+        assert!(!environment.step(&[WAIT, WAIT]).2);
+        assert!(environment.step(&[WAIT, WAIT]).2);
+
+        environment.reset(0);
+        assert!(!environment.step(&[WAIT, WAIT]).2);
+    }
+
+    #[test]
+    fn episodes_are_reproducible() {
+        let map = ["1  #   ", " $   $ ", "  #  2 "].join("\n");
+        let play = |seed: u64| {
+            let mut environment = Environment::new(&map, 500);
+            let mut rng = Rng::new(seed);
+            let mut observation = environment.reset(seed);
+            let size = environment.action_space().size() as u32;
+            for _ in 0..2000 {
+                let actions = [rng.below(size) as usize, rng.below(size) as usize];
+                let (next, _, done) = environment.step(&actions);
+                observation = next;
+                if done {
+                    observation = environment.reset(seed);
+                }
+            }
+            (observation, environment.world().checksum())
+        };
+
+        assert_eq!(play(9), play(9));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod action_space;
+pub mod environment;
+pub mod observation;
+
+pub use self::environment::Environment;
+pub use self::observation::Observation;
@@ -0,0 +1,117 @@
+use models::entity::EntityType;
+use models::world::World;
+
+pub const PLAYER: usize = 0;
+pub const ENEMY: usize = 1;
+pub const OBSTACLE: usize = 2;
+pub const HOLE: usize = 3;
+pub const TREASURE: usize = 4;
+pub const CHANNELS: usize = 5;
+
+/// Fixed size encoding of the world: one `width * height` plane per entity
+/// type class, plus the gold and ammo each agent carries as scalars.
+///
+/// Planes hold 1.0 where an entity of that class is, except the player
+/// plane which holds the player number so agents can tell each other apart.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observation {
+    pub width: usize,
+    pub height: usize,
+    pub planes: Vec<f32>,
+    pub scalars: Vec<f32>,
+}
+
+fn channel(entity_type: EntityType) -> (usize, f32) {
+    match entity_type {
+        EntityType::Player(n) => (PLAYER, f32::from(n)),
+        EntityType::Enemy(_) => (ENEMY, 1.0),
+        EntityType::Obstacle(_) | EntityType::Wagon(_) => (OBSTACLE, 1.0),
+        // Obstacles that may still move
+        EntityType::Pushable(_) => (OBSTACLE, 0.5),
+        EntityType::Hole(_) => (HOLE, 1.0),
+        EntityType::Treasure(_) => (TREASURE, 1.0),
+    }
+}
+
+impl Observation {
+    pub fn encode(world: &World, agents: &[i32]) -> Observation {
+        let width = (world.right_edge.x - world.left_edge.x) as usize + 1;
+        let height = (world.right_edge.y - world.left_edge.y) as usize + 1;
+        let mut planes = vec![0.0; CHANNELS * width * height];
+
+        for entity in world.entities() {
+            let (channel, value) = channel(entity.entity_type);
+            // Footprints may reach past the edge of the world
+            let cells = entity.cells().into_iter();
+            for cell in cells.filter(|cell| cell.is_within(world.left_edge, world.right_edge)) {
+                let x = (cell.x - world.left_edge.x) as usize;
+                let y = (cell.y - world.left_edge.y) as usize;
+                planes[(channel * height + y) * width + x] = value;
+            }
+        }
+
+        let inventories = &world.components().inventories;
+        let scalars = agents
+            .iter()
+            .flat_map(|id| {
+                let inventory = inventories.get(*id).cloned().unwrap_or_default();
+                vec![inventory.gold as f32, inventory.ammo as f32]
+            })
+            .collect();
+
+        Observation {
+            width,
+            height,
+            planes,
+            scalars,
+        }
+    }
+
+    pub fn shape(&self) -> (usize, usize, usize) {
+        (CHANNELS, self.height, self.width)
+    }
+
+    pub fn at(&self, channel: usize, x: usize, y: usize) -> f32 {
+        self.planes[(channel * self.height + y) * self.width + x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::actors::gold;
+    use models::coordinate::Coordinate;
+    use models::entity::Footprint;
+    use serializers::basic;
+
+    #[test]
+    fn encode() {
+        let world = basic::load(&["1 #", "$ 2"].join("\n"));
+        let observation = Observation::encode(&world, &[1, 4]);
+
+        assert_eq!(observation.shape(), (CHANNELS, 2, 3));
+        assert_eq!(observation.planes.len(), CHANNELS * 2 * 3);
+        assert_eq!(observation.at(PLAYER, 0, 0), 1.0);
+        assert_eq!(observation.at(PLAYER, 2, 1), 2.0);
+        assert_eq!(observation.at(OBSTACLE, 2, 0), 1.0);
+        assert_eq!(observation.at(TREASURE, 0, 1), 1.0);
+        assert_eq!(observation.at(ENEMY, 0, 0), 0.0);
+        assert_eq!(observation.planes.iter().filter(|v| **v != 0.0).count(), 4);
+        assert_eq!(observation.scalars, vec![0.0, 6.0, 0.0, 6.0]);
+    }
+
+    #[test]
+    fn skips_cells_off_the_board() {
+        let mut world = basic::load(&["1 ", "  "].join("\n"));
+        let mut hoard = gold::create_at(Coordinate::new(1.0, 1.0));
+        hoard.footprint = Footprint {
+            width: 2,
+            height: 2,
+        };
+        world.register(hoard);
+
+        let observation = Observation::encode(&world, &[1]);
+        assert_eq!(observation.at(TREASURE, 1, 1), 1.0);
+        assert_eq!(observation.planes.iter().filter(|v| **v != 0.0).count(), 2);
+    }
+}
@@ -1,6 +1,9 @@
 pub mod actions;
+pub mod controllers;
 pub mod game;
+pub mod gym;
 pub mod models;
+pub mod net;
 pub mod serializers;
 
 #[cfg(test)]
@@ -1,10 +1,6 @@
 use models::coordinate::Coordinate;
-use models::entity::{Entity, EntityType};
+use models::entity::Entity;
 
 pub fn create_at(coord: Coordinate) -> Entity {
-    Entity {
-        entity_type: EntityType::Enemy('B'),
-        coord,
-        ..Default::default()
-    }
+    super::builtin("bandit", coord)
 }
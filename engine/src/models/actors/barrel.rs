@@ -0,0 +1,7 @@
+use models::coordinate::Coordinate;
+use models::entity::Entity;
+
+/// A barrel, pushed around by whoever walks into it.
+pub fn create_at(coord: Coordinate) -> Entity {
+    super::builtin("barrel", coord)
+}
@@ -0,0 +1,6 @@
+use models::coordinate::Coordinate;
+use models::entity::Entity;
+
+pub fn create_at(coord: Coordinate) -> Entity {
+    super::builtin("gold", coord)
+}
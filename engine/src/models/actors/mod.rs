@@ -1,4 +1,20 @@
+//! Shortcuts to the built-in actors, as defined in `actors.txt`. Other
+//! actors are spawned through a `Prefabs` registry.
 pub mod bandid;
+pub mod barrel;
+pub mod gold;
 pub mod mountain;
 pub mod player;
+pub mod town;
+pub mod wagon;
 pub mod water;
+
+use models::coordinate::Coordinate;
+use models::entity::Entity;
+use models::prefabs::Prefabs;
+
+fn builtin(name: &str, coord: Coordinate) -> Entity {
+    Prefabs::builtin()
+        .spawn(name, coord)
+        .unwrap_or_else(|| panic!("No built-in actor `{}`", name))
+}
@@ -1,10 +1,13 @@
 use models::coordinate::Coordinate;
-use models::entity::{Entity, EntityType};
+use models::entity::Entity;
+use models::prefabs::Prefabs;
+
+/// Most players a map can hold, numbered from 1.
+pub const MAX: i8 = 8;
 
 pub fn create_at(number: i8, coord: Coordinate) -> Entity {
-    Entity {
-        entity_type: EntityType::Player(number),
-        coord,
-        ..Default::default()
-    }
+    Prefabs::builtin()
+        .player()
+        .expect("No built-in player")
+        .create_player(number, coord)
 }
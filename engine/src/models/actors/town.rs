@@ -0,0 +1,7 @@
+use models::coordinate::Coordinate;
+use models::entity::Entity;
+
+/// A town building, covering several tiles from `coord`.
+pub fn create_at(coord: Coordinate) -> Entity {
+    super::builtin("town", coord)
+}
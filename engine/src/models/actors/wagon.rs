@@ -0,0 +1,20 @@
+use models::coordinate::Coordinate;
+use models::entity::Entity;
+
+/// A loose wagon, waiting to be hitched.
+pub fn create_at(coord: Coordinate) -> Entity {
+    super::builtin("wagon", coord)
+}
+
+/// A wagon hitched to `leader`, belonging to the same owner.
+pub fn create_behind(leader: &Entity, coord: Coordinate) -> Entity {
+    hitch(&create_at(coord), leader)
+}
+
+pub fn hitch(wagon: &Entity, leader: &Entity) -> Entity {
+    Entity {
+        owner: leader.owner,
+        leader: Some(leader.id),
+        ..*wagon
+    }
+}
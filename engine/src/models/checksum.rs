@@ -0,0 +1,375 @@
+use actions::action::{ActionData, Payload};
+use game::victory::Victory;
+use models::coordinate::Coordinate;
+use models::entity::{Entity, EntityType};
+use models::fov::Tile;
+use models::owner::Owner;
+use models::rules::{Connectivity, Rules};
+use models::visibility::Visibility;
+use models::world::World;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a hasher. Unlike `std`'s `DefaultHasher` its output is fixed, so
+/// checksums can be compared across runs, machines and compiler versions.
+pub struct Checksum {
+    state: u64,
+}
+
+impl Default for Checksum {
+    fn default() -> Checksum {
+        Checksum { state: FNV_OFFSET }
+    }
+}
+
+impl Checksum {
+    pub fn new() -> Checksum {
+        Default::default()
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.state ^= u64::from(value);
+        self.state = self.state.wrapping_mul(FNV_PRIME);
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        value.to_le_bytes().iter().for_each(|b| self.write_u8(*b));
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        value.to_le_bytes().iter().for_each(|b| self.write_u8(*b));
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32);
+    }
+
+    pub fn write_f32(&mut self, value: f32) {
+        // -0.0 and 0.0 are the same position
+        let value = if value == 0.0 { 0.0 } else { value };
+        self.write_u32(value.to_bits());
+    }
+
+    pub fn write_char(&mut self, value: char) {
+        self.write_u32(value as u32);
+    }
+
+    /// Writes the length first, so `"ab", "c"` and `"a", "bc"` differ.
+    pub fn write_str(&mut self, value: &str) {
+        self.write_u32(value.len() as u32);
+        value.bytes().for_each(|b| self.write_u8(b));
+    }
+
+    pub fn write_coordinate(&mut self, coord: Coordinate) {
+        self.write_f32(coord.x);
+        self.write_f32(coord.y);
+    }
+
+    pub fn write_owner(&mut self, owner: Owner) {
+        match owner {
+            Owner::Engine => self.write_u8(0),
+            Owner::Player(n) => {
+                self.write_u8(1);
+                self.write_u8(n as u8);
+            }
+            Owner::Outlaws => self.write_u8(2),
+        }
+    }
+
+    pub fn write_entity(&mut self, entity: &Entity) {
+        self.write_i32(entity.id);
+        self.write_coordinate(entity.coord);
+        match entity.entity_type {
+            EntityType::Player(n) => {
+                self.write_u8(0);
+                self.write_u8(n as u8);
+            }
+            EntityType::Enemy(c) => {
+                self.write_u8(1);
+                self.write_char(c);
+            }
+            EntityType::Obstacle(c) => {
+                self.write_u8(2);
+                self.write_char(c);
+            }
+            EntityType::Hole(c) => {
+                self.write_u8(3);
+                self.write_char(c);
+            }
+            EntityType::Treasure(c) => {
+                self.write_u8(4);
+                self.write_char(c);
+            }
+            EntityType::Wagon(c) => {
+                self.write_u8(5);
+                self.write_char(c);
+            }
+            EntityType::Pushable(c) => {
+                self.write_u8(6);
+                self.write_char(c);
+            }
+        }
+        self.write_owner(entity.owner);
+        self.write_i32(entity.inventory.gold);
+        self.write_i32(entity.inventory.ammo);
+        self.write_i32(entity.footprint.width);
+        self.write_i32(entity.footprint.height);
+        match entity.leader {
+            Some(leader) => {
+                self.write_u8(1);
+                self.write_i32(leader);
+            }
+            None => self.write_u8(0),
+        }
+        match entity.health {
+            Some(health) => {
+                self.write_u8(1);
+                self.write_i32(health.current);
+                self.write_i32(health.max);
+            }
+            None => self.write_u8(0),
+        }
+        match entity.terrain {
+            Some(terrain) => {
+                self.write_u8(1);
+                self.write_u8(terrain.blocks_sight as u8);
+                self.write_u8(terrain.passable as u8);
+            }
+            None => self.write_u8(0),
+        }
+    }
+
+    pub fn write_rules(&mut self, rules: &Rules) {
+        match rules.connectivity {
+            Connectivity::Four => self.write_u8(0),
+            Connectivity::Eight => self.write_u8(1),
+            Connectivity::Hex => self.write_u8(2),
+        }
+        self.write_u8(rules.corner_cutting as u8);
+        self.write_u32(rules.push_limit as u32);
+        self.write_i32(rules.sight_radius);
+        self.write_i32(rules.shot_range);
+        self.write_f32(rules.hit_chance);
+        self.write_f32(rules.cover_bonus);
+        self.write_i32(rules.damage);
+        self.write_coordinate(rules.board);
+    }
+
+    pub fn write_victory(&mut self, victory: &Victory) {
+        match victory {
+            Victory::Gold(gold) => {
+                self.write_u8(0);
+                self.write_i32(*gold);
+            }
+            Victory::LastStanding => self.write_u8(1),
+            Victory::Richest(turns) => {
+                self.write_u8(2);
+                self.write_u32(*turns);
+            }
+            Victory::Reach(coord) => {
+                self.write_u8(3);
+                self.write_coordinate(*coord);
+            }
+        }
+    }
+
+    pub fn write_visibility(&mut self, visibility: &Visibility) {
+        for tiles in &[visibility.visible(), visibility.explored()] {
+            self.write_u32(tiles.len() as u32);
+            tiles.iter().for_each(|tile| self.write_tile(*tile));
+        }
+    }
+
+    fn write_tile(&mut self, (x, y): Tile) {
+        self.write_i32(x);
+        self.write_i32(y);
+    }
+
+    pub fn write_action(&mut self, action: &ActionData) {
+        self.write_i32(action.entity_id);
+        self.write_str(action.kind.name());
+        match action.payload {
+            Payload::None => self.write_u8(0),
+            Payload::Direction(dir) => {
+                self.write_u8(1);
+                self.write_f32(dir.dx);
+                self.write_f32(dir.dy);
+            }
+            Payload::Target(target) => {
+                self.write_u8(2);
+                self.write_i32(target);
+            }
+            Payload::Values(values) => {
+                self.write_u8(3);
+                self.write_u32(values.as_slice().len() as u32);
+                values.as_slice().iter().for_each(|v| self.write_i32(*v));
+            }
+        }
+        self.write_owner(action.issuer);
+    }
+}
+
+/// Returns a checksum of the whole world state. Entities are hashed in id
+/// order, the order in which `World` stores them, and visibility in player
+/// order.
+pub fn of_world(world: &World) -> u64 {
+    let mut checksum = Checksum::new();
+
+    checksum.write_coordinate(world.left_edge);
+    checksum.write_coordinate(world.right_edge);
+    checksum.write_i32(world.current_id());
+    checksum.write_u64(world.rng().seed());
+    checksum.write_u64(world.rng().state());
+    checksum.write_u32(world.turn());
+    checksum.write_rules(world.rules());
+
+    checksum.write_u32(world.victory().len() as u32);
+    world
+        .victory()
+        .iter()
+        .for_each(|victory| checksum.write_victory(victory));
+
+    checksum.write_u32(world.visibilities().len() as u32);
+    for (player, visibility) in world.visibilities() {
+        checksum.write_u8(*player as u8);
+        checksum.write_visibility(visibility);
+    }
+
+    checksum.write_u32(world.entity_count() as u32);
+    world
+        .entities()
+        .for_each(|entity| checksum.write_entity(&entity));
+
+    let actions = world.get_actions();
+    checksum.write_u32(actions.len() as u32);
+    actions
+        .iter()
+        .for_each(|action| checksum.write_action(action));
+
+    checksum.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions::movement;
+    use models::actors::{bandid, player};
+    use serializers::basic;
+
+    #[test]
+    fn empty_world_is_stable() {
+        assert_eq!(of_world(&World::new()), 0x09ed_fbe9_195a_2565);
+    }
+
+    #[test]
+    fn independent_of_insertion_order() {
+        let mut first = World::new();
+        first.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+        first.register(bandid::create_at(Coordinate::new(3.0, 2.0)));
+
+        let mut second = World::new();
+        let player = second.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+        let bandid = second.register(bandid::create_at(Coordinate::new(3.0, 2.0)));
+        second.retain_entities(|_| false);
+        second.update_entity(bandid);
+        second.update_entity(player);
+
+        assert_eq!(of_world(&first), of_world(&second));
+    }
+
+    #[test]
+    fn same_map_same_checksum() {
+        let map = ["1  #  ~", "  B   2"].join("\n");
+        assert_eq!(of_world(&basic::load(&map)), of_world(&basic::load(&map)));
+    }
+
+    #[test]
+    fn changes_with_state() {
+        let mut world = World::new();
+        let entity = world.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+        let initial = of_world(&world);
+
+        world.register_action(movement::down(entity.id));
+        let with_action = of_world(&world);
+        assert_ne!(initial, with_action);
+
+        world.clear_actions();
+        assert_eq!(initial, of_world(&world));
+
+        world.update_entity(entity.with_coordinate(Coordinate::new(0.0, 1.0)));
+        let moved = of_world(&world);
+        assert_ne!(initial, moved);
+
+        world.rng_mut().next_u64();
+        assert_ne!(moved, of_world(&world));
+    }
+
+    #[test]
+    fn changes_with_turn() {
+        let mut world = World::new();
+        let initial = of_world(&world);
+
+        world.end_turn();
+        assert_ne!(initial, of_world(&world));
+    }
+
+    #[test]
+    fn changes_with_rules() {
+        let mut world = World::new();
+        let initial = of_world(&world);
+
+        world.set_rules(Rules::hex());
+        let hex = of_world(&world);
+        assert_ne!(initial, hex);
+
+        world.set_rules(Rules {
+            damage: 2,
+            ..Rules::hex()
+        });
+        assert_ne!(hex, of_world(&world));
+    }
+
+    #[test]
+    fn changes_with_victory() {
+        let mut world = World::new();
+        let initial = of_world(&world);
+
+        world.set_victory(vec![Victory::Gold(20)]);
+        let gold = of_world(&world);
+        assert_ne!(initial, gold);
+
+        world.set_victory(vec![Victory::Gold(30)]);
+        assert_ne!(gold, of_world(&world));
+    }
+
+    #[test]
+    fn changes_with_visibility() {
+        let mut world = basic::load(&["1   ", "    "].join("\n"));
+        world.update_visibility();
+        let initial = of_world(&world);
+
+        let mut visibility = Visibility::new();
+        visibility.update(vec![(0, 0)].into_iter().collect());
+        world.restore_visibility(1, visibility);
+        let fogged = of_world(&world);
+        assert_ne!(initial, fogged);
+
+        world.restore_visibility(2, Visibility::new());
+        assert_ne!(fogged, of_world(&world));
+    }
+
+    #[test]
+    fn negative_zero() {
+        let mut zero = Checksum::new();
+        zero.write_f32(0.0);
+        let mut negative_zero = Checksum::new();
+        negative_zero.write_f32(-0.0);
+
+        assert_eq!(zero.finish(), negative_zero.finish());
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::btree_map::{self, BTreeMap};
+
+use models::coordinate::Coordinate;
+use models::entity::{Entity, EntityType, Footprint};
+use models::inventory::Inventory;
+use models::owner::Owner;
+
+/// One kind of component, keyed by entity id. Ordered by id, so every
+/// iteration over a store is deterministic.
+#[derive(Clone, Debug)]
+pub struct Store<T> {
+    components: BTreeMap<i32, T>,
+}
+
+impl<T> Default for Store<T> {
+    fn default() -> Store<T> {
+        Store {
+            components: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> Store<T> {
+    pub fn insert(&mut self, id: i32, component: T) -> Option<T> {
+        self.components.insert(id, component)
+    }
+
+    pub fn get(&self, id: i32) -> Option<&T> {
+        self.components.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: i32) -> Option<&mut T> {
+        self.components.get_mut(&id)
+    }
+
+    pub fn remove(&mut self, id: i32) -> Option<T> {
+        self.components.remove(&id)
+    }
+
+    pub fn contains(&self, id: i32) -> bool {
+        self.components.contains_key(&id)
+    }
+
+    /// Iterates over the components in id order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.components.iter(),
+        }
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = i32> + '_ {
+        self.components.keys().cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Sets `id`'s component, or removes it on `None`.
+    pub fn set(&mut self, id: i32, component: Option<T>) {
+        match component {
+            Some(component) => self.insert(id, component),
+            None => self.remove(id),
+        };
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: btree_map::Iter<'a, i32, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (i32, &'a T);
+
+    fn next(&mut self) -> Option<(i32, &'a T)> {
+        self.inner.next().map(|(id, component)| (*id, component))
+    }
+}
+
+/// Where an entity stands and the tiles it covers.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Position {
+    pub coord: Coordinate,
+    pub footprint: Footprint,
+}
+
+impl Position {
+    /// Every tile covered, the coordinate first.
+    pub fn cells(&self) -> Vec<Coordinate> {
+        let mut cells = vec![];
+        for dy in 0..self.footprint.height {
+            for dx in 0..self.footprint.width {
+                cells.push(self.coord.translate(dx as f32, dy as f32));
+            }
+        }
+        cells
+    }
+
+    pub fn occupies(&self, coord: Coordinate) -> bool {
+        self.cells().contains(&coord)
+    }
+}
+
+/// What an entity is, as serializers and frontends draw it.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Renderable {
+    pub entity_type: EntityType,
+}
+
+/// Who decides what an entity does.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Controller {
+    pub owner: Owner,
+    /// Entity this one follows, stepping into the tile it leaves.
+    pub leader: Option<i32>,
+}
+
+/// Hits an entity takes before it's removed.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Health {
+    pub current: i32,
+    pub max: i32,
+}
+
+/// Entities without health go down on the first hit.
+impl Default for Health {
+    fn default() -> Health {
+        Health::new(1)
+    }
+}
+
+impl Health {
+    pub fn new(max: i32) -> Health {
+        Health { current: max, max }
+    }
+
+    pub fn damaged(self, damage: i32) -> Health {
+        Health {
+            current: self.current - damage,
+            ..self
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0
+    }
+}
+
+/// Part of the map rather than something on it: players remember terrain
+/// they explored.
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub struct Terrain {
+    pub blocks_sight: bool,
+    /// Walked through rather than blocking moves.
+    pub passable: bool,
+}
+
+/// Every component of a world, one store per kind. Each entity has a
+/// position, renderable, controller and inventory; health and terrain are
+/// optional.
+#[derive(Clone, Debug, Default)]
+pub struct Components {
+    pub positions: Store<Position>,
+    pub renderables: Store<Renderable>,
+    pub controllers: Store<Controller>,
+    pub inventories: Store<Inventory>,
+    pub healths: Store<Health>,
+    pub terrain: Store<Terrain>,
+}
+
+impl Components {
+    /// Splits `entity` into its components, replacing any it had.
+    pub fn insert(&mut self, entity: &Entity) {
+        let id = entity.id;
+        self.positions.insert(id, entity.position());
+        self.renderables.insert(
+            id,
+            Renderable {
+                entity_type: entity.entity_type,
+            },
+        );
+        self.controllers.insert(
+            id,
+            Controller {
+                owner: entity.owner,
+                leader: entity.leader,
+            },
+        );
+        self.inventories.insert(id, entity.inventory);
+        self.healths.set(id, entity.health);
+        self.terrain.set(id, entity.terrain);
+    }
+
+    /// Gathers the components of `id` back into an entity.
+    pub fn get(&self, id: i32) -> Option<Entity> {
+        let position = self.positions.get(id)?;
+        let controller = self.controllers.get(id)?;
+
+        Some(Entity {
+            id,
+            coord: position.coord,
+            footprint: position.footprint,
+            entity_type: self.renderables.get(id)?.entity_type,
+            owner: controller.owner,
+            leader: controller.leader,
+            inventory: *self.inventories.get(id)?,
+            health: self.healths.get(id).cloned(),
+            terrain: self.terrain.get(id).cloned(),
+        })
+    }
+
+    pub fn remove(&mut self, id: i32) -> Option<Entity> {
+        let entity = self.get(id);
+        self.positions.remove(id);
+        self.renderables.remove(id);
+        self.controllers.remove(id);
+        self.inventories.remove(id);
+        self.healths.remove(id);
+        self.terrain.remove(id);
+        entity
+    }
+
+    /// Ids of every entity, in order.
+    pub fn ids(&self) -> impl Iterator<Item = i32> + '_ {
+        self.positions.ids()
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::actors::{mountain, player};
+
+    #[test]
+    fn store() {
+        let mut store = Store::default();
+        store.insert(3, 'c');
+        store.insert(1, 'a');
+
+        assert_eq!(store.get(1), Some(&'a'));
+        assert!(!store.contains(2));
+        assert_eq!(store.iter().collect::<Vec<_>>(), vec![(1, &'a'), (3, &'c')]);
+
+        *store.get_mut(3).unwrap() = 'b';
+        store.set(1, None);
+        assert_eq!(store.ids().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(store.remove(3), Some('b'));
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn split_and_gather() {
+        let mut components = Components::default();
+        let entity = player::create_at(2, Coordinate::new(1.0, 2.0)).with_id(7);
+        components.insert(&entity);
+
+        assert_eq!(components.healths.get(7), entity.health.as_ref());
+        assert!(!components.terrain.contains(7));
+
+        let gathered = components.get(7).unwrap();
+        assert_eq!(gathered.position(), entity.position());
+        assert_eq!(gathered.owner, Owner::Player(2));
+        assert_eq!(gathered.inventory, entity.inventory);
+
+        components.insert(&mountain::create_at(Coordinate::new(0.0, 0.0)).with_id(7));
+        assert!(!components.healths.contains(7));
+        assert!(components.terrain.get(7).unwrap().blocks_sight);
+
+        assert!(components.remove(7).is_some());
+        assert!(components.is_empty());
+        assert!(components.get(7).is_none());
+    }
+
+    #[test]
+    fn health() {
+        let health = Health::new(2).damaged(1);
+        assert_eq!(health.current, 1);
+        assert!(!health.is_dead());
+        assert!(health.damaged(1).is_dead());
+        assert!(Health::default().damaged(1).is_dead());
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use models::topology::{Square8, Topology};
+
 #[derive(Clone, Default, Debug, Copy)]
 pub struct Coordinate {
     pub x: f32,
@@ -24,10 +26,10 @@ impl Coordinate {
         Coordinate::new(self.x + dx, self.y + dy)
     }
 
+    /// Adjacency on square tiles, diagonals included. See
+    /// `Topology::is_adjacent` for other grids.
     pub fn is_adjacent(self, other: Coordinate) -> bool {
-        let dx: f32 = self.x - other.x;
-        let dy: f32 = self.y - other.y;
-        dx < 2.0 && dx > -2.0 && dy < 2.0 && dy > -2.0
+        Square8.is_adjacent(self, other)
     }
 
     pub fn is_at_x(self, x: f32) -> bool {
@@ -1,6 +1,6 @@
 use std::fmt;
 
-#[derive(Clone, Default, Debug, Copy)]
+#[derive(Clone, Default, Debug, Copy, PartialEq)]
 pub struct Direction {
     pub dx: f32,
     pub dy: f32,
@@ -11,10 +11,26 @@ pub const RIGHT: Direction = Direction { dx: 1.0, dy: 0.0 };
 pub const UP: Direction = Direction { dx: 0.0, dy: -1.0 };
 pub const DOWN: Direction = Direction { dx: 0.0, dy: 1.0 };
 
+pub const UP_RIGHT: Direction = Direction { dx: 1.0, dy: -1.0 };
+pub const DOWN_RIGHT: Direction = Direction { dx: 1.0, dy: 1.0 };
+pub const DOWN_LEFT: Direction = Direction { dx: -1.0, dy: 1.0 };
+pub const UP_LEFT: Direction = Direction { dx: -1.0, dy: -1.0 };
+
+pub const ALL: [Direction; 4] = [UP, RIGHT, DOWN, LEFT];
+
+/// Clockwise from up, diagonals included.
+pub const ALL_EIGHT: [Direction; 8] = [
+    UP, UP_RIGHT, RIGHT, DOWN_RIGHT, DOWN, DOWN_LEFT, LEFT, UP_LEFT,
+];
+
 impl fmt::Display for Direction {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Dir[x: {}, y: {}]", self.dx, self.dy)
     }
 }
 
-impl Direction {}
+impl Direction {
+    pub fn is_diagonal(self) -> bool {
+        self.dx != 0.0 && self.dy != 0.0
+    }
+}
@@ -1,4 +1,7 @@
+use models::components::{Health, Position, Terrain};
 use models::coordinate::Coordinate;
+use models::inventory::Inventory;
+use models::owner::Owner;
 
 #[derive(Clone, Debug, Copy, PartialEq)]
 pub enum EntityType {
@@ -6,13 +9,58 @@ pub enum EntityType {
     Enemy(char),
     Obstacle(char),
     Hole(char),
+    Treasure(char),
+    /// Trails behind its leader, like a snake's body.
+    Wagon(char),
+    /// Slides a tile away when walked into, filling any hole it lands in.
+    Pushable(char),
 }
 
+/// Tiles an entity covers, from its coordinate towards the bottom right.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Footprint {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Default for Footprint {
+    fn default() -> Footprint {
+        Footprint {
+            width: 1,
+            height: 1,
+        }
+    }
+}
+
+/// An entity's components bundled together, as prefabs create them and the
+/// world hands them out. A copy: changing it leaves the world alone.
 #[derive(Clone, Debug, Copy)]
 pub struct Entity {
     pub id: i32,
     pub coord: Coordinate,
     pub entity_type: EntityType,
+    pub owner: Owner,
+    pub inventory: Inventory,
+    pub footprint: Footprint,
+    /// Entity this one follows, stepping into the tile it leaves.
+    pub leader: Option<i32>,
+    pub health: Option<Health>,
+    pub terrain: Option<Terrain>,
+}
+
+impl EntityType {
+    /// The tile it's drawn as in maps, players as their number.
+    pub fn glyph(self) -> char {
+        match self {
+            EntityType::Player(n) => (b'0' + n as u8) as char,
+            EntityType::Enemy(c)
+            | EntityType::Obstacle(c)
+            | EntityType::Hole(c)
+            | EntityType::Treasure(c)
+            | EntityType::Wagon(c)
+            | EntityType::Pushable(c) => c,
+        }
+    }
 }
 
 impl Default for Entity {
@@ -21,6 +69,12 @@ impl Default for Entity {
             id: 0,
             coord: Coordinate::new(0.0, 0.0),
             entity_type: EntityType::Player(1),
+            owner: Owner::Engine,
+            inventory: Default::default(),
+            footprint: Default::default(),
+            leader: None,
+            health: None,
+            terrain: None,
         }
     }
 }
@@ -31,6 +85,12 @@ impl Entity {
             id,
             coord,
             entity_type: EntityType::Enemy('?'),
+            owner: Owner::Engine,
+            inventory: Default::default(),
+            footprint: Default::default(),
+            leader: None,
+            health: None,
+            terrain: None,
         }
     }
 
@@ -38,6 +98,48 @@ impl Entity {
         Entity { coord, ..*self }
     }
 
+    pub fn with_inventory(&self, inventory: Inventory) -> Entity {
+        Entity { inventory, ..*self }
+    }
+
+    pub fn position(&self) -> Position {
+        Position {
+            coord: self.coord,
+            footprint: self.footprint,
+        }
+    }
+
+    /// Every tile the entity covers, its own coordinate first.
+    pub fn cells(&self) -> Vec<Coordinate> {
+        self.position().cells()
+    }
+
+    pub fn occupies(&self, coord: Coordinate) -> bool {
+        self.cells().contains(&coord)
+    }
+
+    pub fn is_player(&self) -> bool {
+        matches!(self.entity_type, EntityType::Player(_))
+    }
+
+    pub fn is_treasure(&self) -> bool {
+        matches!(self.entity_type, EntityType::Treasure(_))
+    }
+
+    /// Whether others can step onto it: treasure is picked up, and some
+    /// terrain is walked through.
+    pub fn is_passable(&self) -> bool {
+        self.is_treasure() || self.terrain.is_some_and(|terrain| terrain.passable)
+    }
+
+    pub fn is_pushable(&self) -> bool {
+        matches!(self.entity_type, EntityType::Pushable(_))
+    }
+
+    pub fn is_hole(&self) -> bool {
+        matches!(self.entity_type, EntityType::Hole(_))
+    }
+
     pub fn with_id(&self, new_id: i32) -> Entity {
         Entity {
             id: new_id,
@@ -56,5 +158,23 @@ mod tests {
         assert_eq!(1, entity.id);
         assert!(entity.coord.is_at_x(2.0));
         assert!(entity.coord.is_at_y(3.0));
+        assert_eq!(entity.cells(), vec![entity.coord]);
+    }
+
+    #[test]
+    fn footprint() {
+        let entity = Entity {
+            footprint: Footprint {
+                width: 2,
+                height: 2,
+            },
+            ..Entity::new(1, Coordinate::new(2.0, 3.0))
+        };
+
+        assert_eq!(entity.cells().len(), 4);
+        assert!(entity.occupies(Coordinate::new(2.0, 3.0)));
+        assert!(entity.occupies(Coordinate::new(3.0, 4.0)));
+        assert!(!entity.occupies(Coordinate::new(4.0, 3.0)));
+        assert!(!entity.occupies(Coordinate::new(1.0, 3.0)));
     }
 }
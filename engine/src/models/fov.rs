@@ -0,0 +1,271 @@
+use std::collections::BTreeSet;
+
+use models::coordinate::Coordinate;
+use models::rules::Connectivity;
+use models::world::World;
+
+/// How far entities see by default, in tiles.
+pub const SIGHT_RADIUS: i32 = 8;
+
+/// A tile, as integer coordinates.
+pub type Tile = (i32, i32);
+
+pub fn tile(coord: Coordinate) -> Tile {
+    (coord.x as i32, coord.y as i32)
+}
+
+// Transforms mapping the first octant onto each of the eight
+const OCTANTS: [[i32; 4]; 8] = [
+    [1, 0, 0, 1],
+    [0, 1, 1, 0],
+    [0, -1, 1, 0],
+    [-1, 0, 0, 1],
+    [-1, 0, 0, -1],
+    [0, -1, -1, 0],
+    [0, 1, -1, 0],
+    [1, 0, 0, -1],
+];
+
+struct Caster {
+    origin: Tile,
+    radius: i32,
+    left_edge: Tile,
+    right_edge: Tile,
+    opaque: BTreeSet<Tile>,
+    visible: BTreeSet<Tile>,
+}
+
+impl Caster {
+    fn is_inside(&self, (x, y): Tile) -> bool {
+        x >= self.left_edge.0
+            && x <= self.right_edge.0
+            && y >= self.left_edge.1
+            && y <= self.right_edge.1
+    }
+
+    fn blocks(&self, tile: Tile) -> bool {
+        !self.is_inside(tile) || self.opaque.contains(&tile)
+    }
+
+    /// Scans `row` onwards between slopes `start` and `end`, recursing
+    /// whenever a blocker splits the light.
+    fn cast(&mut self, row: i32, mut start: f32, end: f32, octant: [i32; 4]) {
+        if start < end {
+            return;
+        }
+
+        let [xx, xy, yx, yy] = octant;
+        let mut next_start = start;
+        for distance in row..=self.radius {
+            let dy = -distance;
+            let mut blocked = false;
+
+            for dx in -distance..=0 {
+                let left_slope = (dx as f32 - 0.5) / (dy as f32 + 0.5);
+                let right_slope = (dx as f32 + 0.5) / (dy as f32 - 0.5);
+                if start < right_slope {
+                    continue;
+                }
+                if end > left_slope {
+                    break;
+                }
+
+                let tile = (
+                    self.origin.0 + dx * xx + dy * xy,
+                    self.origin.1 + dx * yx + dy * yy,
+                );
+                if dx * dx + dy * dy <= self.radius * self.radius && self.is_inside(tile) {
+                    self.visible.insert(tile);
+                }
+
+                if blocked {
+                    if self.blocks(tile) {
+                        next_start = right_slope;
+                    } else {
+                        blocked = false;
+                        start = next_start;
+                    }
+                } else if self.blocks(tile) && distance < self.radius {
+                    blocked = true;
+                    self.cast(distance + 1, start, left_slope, octant);
+                    next_start = right_slope;
+                }
+            }
+
+            if blocked {
+                break;
+            }
+        }
+    }
+}
+
+/// Tiles holding terrain that blocks sight.
+fn opaque_tiles(world: &World) -> BTreeSet<Tile> {
+    let components = world.components();
+    components
+        .terrain
+        .iter()
+        .filter(|(_, terrain)| terrain.blocks_sight)
+        .filter_map(|(id, _)| components.positions.get(id))
+        .flat_map(|position| position.cells())
+        .map(tile)
+        .collect()
+}
+
+/// Returns `true` if no obstacle stands between `from` and `to`. The ends
+/// themselves don't block.
+pub fn has_line_of_sight(world: &World, from: Coordinate, to: Coordinate) -> bool {
+    let opaque = opaque_tiles(world);
+    let tiles = world.rules().topology().line(from, to);
+
+    tiles.len() < 3
+        || tiles[1..tiles.len() - 1]
+            .iter()
+            .all(|coord| !opaque.contains(&tile(*coord)))
+}
+
+/// Tiles seen from `origin`. Obstacles block sight but are seen themselves;
+/// holes such as water don't block it.
+pub fn visible_from(world: &World, origin: Coordinate, radius: i32) -> BTreeSet<Tile> {
+    match world.rules().connectivity {
+        Connectivity::Hex => cast_rays(world, origin, radius),
+        _ => shadowcast(world, origin, radius),
+    }
+}
+
+/// Casts a line to every tile in range, for grids shadowcasting doesn't
+/// handle.
+fn cast_rays(world: &World, origin: Coordinate, radius: i32) -> BTreeSet<Tile> {
+    let topology = world.rules().topology();
+    let (left, right) = (tile(world.left_edge), tile(world.right_edge));
+
+    let mut visible = BTreeSet::new();
+    for y in left.1..=right.1 {
+        for x in left.0..=right.0 {
+            let coord = Coordinate::new(x as f32, y as f32);
+            if topology.distance(origin, coord) <= radius && has_line_of_sight(world, origin, coord)
+            {
+                visible.insert((x, y));
+            }
+        }
+    }
+
+    visible
+}
+
+/// Recursive shadowcasting over square tiles.
+fn shadowcast(world: &World, origin: Coordinate, radius: i32) -> BTreeSet<Tile> {
+    let opaque = opaque_tiles(world);
+
+    let mut caster = Caster {
+        origin: tile(origin),
+        radius,
+        left_edge: tile(world.left_edge),
+        right_edge: tile(world.right_edge),
+        opaque,
+        visible: BTreeSet::new(),
+    };
+
+    if caster.is_inside(caster.origin) {
+        caster.visible.insert(caster.origin);
+    }
+    for octant in OCTANTS.iter() {
+        caster.cast(1, 1.0, 0.0, *octant);
+    }
+
+    caster.visible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::rules::Rules;
+    use serializers::basic;
+
+    fn sees(map: &str, from: Tile) -> BTreeSet<Tile> {
+        let world = basic::load(map);
+        visible_from(
+            &world,
+            Coordinate::new(from.0 as f32, from.1 as f32),
+            SIGHT_RADIUS,
+        )
+    }
+
+    #[test]
+    fn open_field() {
+        let visible = sees(&["     ", "     ", "     "].join("\n"), (2, 1));
+        assert_eq!(visible.len(), 15);
+    }
+
+    #[test]
+    fn limited_by_radius() {
+        let world = World::create(Coordinate::new(20.0, 0.0));
+        let visible = visible_from(&world, Coordinate::new(0.0, 0.0), 3);
+        assert_eq!(visible, (0..4).map(|x| (x, 0)).collect());
+    }
+
+    #[test]
+    fn mountains_block_sight() {
+        let visible = sees("1 #  ", (0, 0));
+        assert!(visible.contains(&(1, 0)));
+        assert!(visible.contains(&(2, 0)));
+        assert!(!visible.contains(&(3, 0)));
+        assert!(!visible.contains(&(4, 0)));
+    }
+
+    #[test]
+    fn water_does_not() {
+        let visible = sees("1 ~~ ", (0, 0));
+        assert_eq!(visible.len(), 5);
+    }
+
+    #[test]
+    fn shadows() {
+        let map = ["     ", "  #  ", "     ", "     "].join("\n");
+        let visible = sees(&map, (2, 3));
+        assert!(visible.contains(&(2, 1)));
+        assert!(!visible.contains(&(2, 0)));
+        assert!(visible.contains(&(0, 0)));
+        assert!(visible.contains(&(4, 0)));
+    }
+
+    #[test]
+    fn line_of_sight() {
+        let world = basic::load(&["1 # 2", "  ~  "].join("\n"));
+        let at = |x, y| Coordinate::new(x, y);
+
+        assert!(!has_line_of_sight(&world, at(0.0, 0.0), at(4.0, 0.0)));
+        assert!(has_line_of_sight(&world, at(0.0, 1.0), at(4.0, 1.0)));
+        assert!(has_line_of_sight(&world, at(0.0, 0.0), at(2.0, 0.0)));
+        assert!(has_line_of_sight(&world, at(1.0, 0.0), at(3.0, 1.0)));
+    }
+
+    #[test]
+    fn hexes() {
+        let mut world = basic::load(&["     ", "  #  ", "     "].join("\n"));
+        world.set_rules(Rules::hex());
+        let visible = visible_from(&world, Coordinate::new(2.0, 0.0), SIGHT_RADIUS);
+
+        assert!(visible.contains(&(2, 1)));
+        assert!(!visible.contains(&(2, 2)));
+        assert!(visible.contains(&(0, 2)));
+        assert!(visible.contains(&(4, 1)));
+    }
+
+    #[test]
+    fn towns_block_sight() {
+        let map = ["1 HH  ", "  HH  "].join("\n");
+        let visible = sees(&map, (0, 1));
+        assert!(visible.contains(&(2, 1)));
+        assert!(!visible.contains(&(3, 1)));
+        assert!(!visible.contains(&(4, 1)));
+        assert!(!visible.contains(&(5, 0)));
+    }
+
+    #[test]
+    fn symmetric_walls() {
+        let map = ["#####", "#   #", "#####"].join("\n");
+        let visible = sees(&map, (2, 1));
+        assert_eq!(visible.len(), 15);
+    }
+}
@@ -0,0 +1,44 @@
+/// What an entity carries.
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
+pub struct Inventory {
+    pub gold: i32,
+    pub ammo: i32,
+}
+
+impl Inventory {
+    pub fn with_gold(gold: i32) -> Inventory {
+        Inventory {
+            gold,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_ammo(ammo: i32) -> Inventory {
+        Inventory {
+            ammo,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the inventory holding everything from both.
+    pub fn merge(self, other: Inventory) -> Inventory {
+        Inventory {
+            gold: self.gold + other.gold,
+            ammo: self.ammo + other.ammo,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge() {
+        let merged = Inventory::with_gold(3)
+            .merge(Inventory::with_gold(4))
+            .merge(Inventory::with_ammo(2));
+        assert_eq!(merged.gold, 7);
+        assert_eq!(merged.ammo, 2);
+    }
+}
@@ -1,11 +1,32 @@
 pub mod actors;
+pub mod checksum;
+pub mod components;
 pub mod coordinate;
 pub mod direction;
 pub mod entity;
+pub mod fov;
+pub mod inventory;
+pub mod owner;
+pub mod prefabs;
+pub mod query;
+pub mod rng;
+pub mod rules;
+pub mod topology;
+pub mod view;
+pub mod visibility;
 pub mod world;
 
+pub use self::components::Components;
 pub use self::coordinate::Coordinate;
 pub use self::direction::Direction;
 pub use self::entity::Entity;
 pub use self::entity::EntityType;
+pub use self::inventory::Inventory;
+pub use self::owner::Owner;
+pub use self::prefabs::{Prefab, Prefabs};
+pub use self::query::Query;
+pub use self::rng::Rng;
+pub use self::rules::Rules;
+pub use self::view::WorldView;
+pub use self::visibility::Visibility;
 pub use self::world::World;
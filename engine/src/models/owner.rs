@@ -0,0 +1,37 @@
+/// Who an entity belongs to, and on whose behalf an action is issued.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum Owner {
+    /// The engine itself: owns the terrain and may command any entity.
+    Engine,
+    Player(i8),
+    Outlaws,
+}
+
+impl Owner {
+    /// Returns `true` if an action issued by `self` may command an entity
+    /// owned by `owner`.
+    pub fn controls(self, owner: Owner) -> bool {
+        self == Owner::Engine || self == owner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn engine_controls_everything() {
+        assert!(Owner::Engine.controls(Owner::Engine));
+        assert!(Owner::Engine.controls(Owner::Player(1)));
+        assert!(Owner::Engine.controls(Owner::Outlaws));
+    }
+
+    #[test]
+    fn players_control_their_own() {
+        assert!(Owner::Player(1).controls(Owner::Player(1)));
+        assert!(!Owner::Player(1).controls(Owner::Player(2)));
+        assert!(!Owner::Player(1).controls(Owner::Outlaws));
+        assert!(!Owner::Player(1).controls(Owner::Engine));
+        assert!(!Owner::Outlaws.controls(Owner::Player(1)));
+    }
+}
@@ -0,0 +1,226 @@
+use std::sync::OnceLock;
+
+use models::actors::player;
+use models::components::{Health, Terrain};
+use models::coordinate::Coordinate;
+use models::entity::{Entity, EntityType, Footprint};
+use models::inventory::Inventory;
+use models::owner::Owner;
+use serializers::prefabs;
+
+/// Actors shipped with the engine, as read by `Prefabs::builtin`.
+const BUILTIN: &str = include_str!("../../actors.txt");
+
+/// How frontends draw an actor, each channel from 0 to 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+/// An actor archetype: the components every entity spawned from it starts
+/// with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prefab {
+    pub name: String,
+    /// Players are numbered when spawned, whatever the number here.
+    pub entity_type: EntityType,
+    pub owner: Owner,
+    pub inventory: Inventory,
+    pub health: Option<Health>,
+    pub size: i32,
+    pub terrain: Option<Terrain>,
+    pub color: Option<Color>,
+    /// Name of the bot playing it, as built by `controllers::by_name`.
+    pub ai: Option<String>,
+}
+
+impl Prefab {
+    pub fn new(name: &str, entity_type: EntityType) -> Prefab {
+        Prefab {
+            name: name.to_string(),
+            entity_type,
+            owner: Owner::Engine,
+            inventory: Default::default(),
+            health: None,
+            size: 1,
+            terrain: None,
+            color: None,
+            ai: None,
+        }
+    }
+
+    pub fn is_player(&self) -> bool {
+        matches!(self.entity_type, EntityType::Player(_))
+    }
+
+    pub fn create_at(&self, coord: Coordinate) -> Entity {
+        Entity {
+            entity_type: self.entity_type,
+            coord,
+            owner: self.owner,
+            inventory: self.inventory,
+            health: self.health,
+            terrain: self.terrain,
+            footprint: Footprint {
+                width: self.size,
+                height: self.size,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Spawns the actor for player `number`, owned by that player.
+    pub fn create_player(&self, number: i8, coord: Coordinate) -> Entity {
+        Entity {
+            entity_type: EntityType::Player(number),
+            owner: Owner::Player(number),
+            ..self.create_at(coord)
+        }
+    }
+}
+
+/// The actors a world can be made of, looked up by name or by the glyph
+/// maps draw them with.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Prefabs {
+    prefabs: Vec<Prefab>,
+}
+
+impl Prefabs {
+    pub fn new() -> Prefabs {
+        Default::default()
+    }
+
+    /// The actors shipped with the engine, read once from `actors.txt`.
+    pub fn builtin() -> &'static Prefabs {
+        static BUILTIN_PREFABS: OnceLock<Prefabs> = OnceLock::new();
+        BUILTIN_PREFABS.get_or_init(|| {
+            prefabs::load(BUILTIN)
+                .unwrap_or_else(|error| panic!("Invalid built-in actors: {}", error))
+        })
+    }
+
+    /// Adds `prefab`, replacing any other by the same name.
+    pub fn register(&mut self, prefab: Prefab) {
+        match self
+            .prefabs
+            .iter_mut()
+            .find(|other| other.name == prefab.name)
+        {
+            Some(other) => *other = prefab,
+            None => self.prefabs.push(prefab),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Prefab> {
+        self.prefabs.iter().find(|prefab| prefab.name == name)
+    }
+
+    /// The actor drawn as `glyph`, digits standing for players.
+    pub fn by_glyph(&self, glyph: char) -> Option<&Prefab> {
+        match player_number(glyph) {
+            Some(_) => self.player(),
+            None if glyph.is_ascii_digit() => None,
+            None => self
+                .prefabs
+                .iter()
+                .find(|prefab| !prefab.is_player() && prefab.entity_type.glyph() == glyph),
+        }
+    }
+
+    /// The actor `entity` was spawned from.
+    pub fn of(&self, entity: &Entity) -> Option<&Prefab> {
+        self.by_glyph(entity.entity_type.glyph())
+    }
+
+    pub fn player(&self) -> Option<&Prefab> {
+        self.prefabs.iter().find(|prefab| prefab.is_player())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Prefab> {
+        self.prefabs.iter()
+    }
+
+    pub fn spawn(&self, name: &str, coord: Coordinate) -> Option<Entity> {
+        self.get(name).map(|prefab| prefab.create_at(coord))
+    }
+
+    /// Spawns what `glyph` stands for in maps.
+    pub fn spawn_glyph(&self, glyph: char, coord: Coordinate) -> Option<Entity> {
+        let prefab = self.by_glyph(glyph)?;
+        match player_number(glyph) {
+            Some(number) => Some(prefab.create_player(number, coord)),
+            None => Some(prefab.create_at(coord)),
+        }
+    }
+}
+
+/// The player a digit stands for in maps, from 1 to `player::MAX`.
+fn player_number(glyph: char) -> Option<i8> {
+    glyph
+        .to_digit(10)
+        .map(|number| number as i8)
+        .filter(|number| (1..=player::MAX).contains(number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheriff() -> Prefab {
+        Prefab {
+            owner: Owner::Engine,
+            inventory: Inventory::with_ammo(6),
+            health: Some(Health::new(3)),
+            ..Prefab::new("sheriff", EntityType::Enemy('S'))
+        }
+    }
+
+    #[test]
+    fn builtin() {
+        let prefabs = Prefabs::builtin();
+        let town = prefabs.get("town").unwrap();
+
+        assert_eq!(town.entity_type, EntityType::Obstacle('H'));
+        assert_eq!(town.size, 2);
+        assert!(town.terrain.unwrap().blocks_sight);
+        assert_eq!(prefabs.by_glyph('$').unwrap().inventory.gold, 10);
+        assert_eq!(prefabs.by_glyph('B').unwrap().owner, Owner::Outlaws);
+        assert!(prefabs.by_glyph('?').is_none());
+        assert!(prefabs.by_glyph('9').is_none());
+    }
+
+    #[test]
+    fn spawn_players_by_number() {
+        let player = Prefabs::builtin()
+            .spawn_glyph('3', Coordinate::new(1.0, 2.0))
+            .unwrap();
+
+        assert_eq!(player.entity_type, EntityType::Player(3));
+        assert_eq!(player.owner, Owner::Player(3));
+        assert_eq!(player.inventory.ammo, 6);
+        assert_eq!(Prefabs::builtin().of(&player).unwrap().name, "player");
+    }
+
+    #[test]
+    fn register() {
+        let mut prefabs = Prefabs::builtin().clone();
+        prefabs.register(sheriff());
+
+        let sheriff = prefabs.spawn_glyph('S', Coordinate::new(0.0, 0.0)).unwrap();
+        assert_eq!(sheriff.health, Some(Health::new(3)));
+        assert_eq!(sheriff.inventory.ammo, 6);
+
+        prefabs.register(Prefab::new("sheriff", EntityType::Enemy('$')));
+        assert_eq!(
+            prefabs
+                .iter()
+                .filter(|prefab| prefab.name == "sheriff")
+                .count(),
+            1
+        );
+        assert!(prefabs.by_glyph('S').is_none());
+    }
+}
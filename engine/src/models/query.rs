@@ -0,0 +1,171 @@
+use models::coordinate::Coordinate;
+use models::entity::{Entity, EntityType};
+use models::topology::Topology;
+
+/// Entities of a world or view narrowed down step by step, for frontends and
+/// bots to find what they need without relying on entity ids. Entities stay
+/// in id order, which also breaks ties.
+#[derive(Clone)]
+pub struct Query {
+    entities: Vec<Entity>,
+    topology: &'static dyn Topology,
+}
+
+impl Query {
+    pub fn new<I>(entities: I, topology: &'static dyn Topology) -> Query
+    where
+        I: IntoIterator<Item = Entity>,
+    {
+        Query {
+            entities: entities.into_iter().collect(),
+            topology,
+        }
+    }
+
+    pub fn filter<F>(self, predicate: F) -> Query
+    where
+        F: Fn(&Entity) -> bool,
+    {
+        Query {
+            entities: self
+                .entities
+                .into_iter()
+                .filter(|entity| predicate(entity))
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn of_type(self, entity_type: EntityType) -> Query {
+        self.filter(|entity| entity.entity_type == entity_type)
+    }
+
+    pub fn players(self) -> Query {
+        self.filter(Entity::is_player)
+    }
+
+    /// Entities covering a tile of the rectangle between the two corners,
+    /// both included.
+    pub fn in_rect(self, from: Coordinate, to: Coordinate) -> Query {
+        self.filter(|entity| {
+            entity
+                .cells()
+                .into_iter()
+                .any(|cell| cell.is_within(from, to))
+        })
+    }
+
+    /// Entities covering a tile at most `radius` steps from `center`.
+    pub fn within(self, center: Coordinate, radius: i32) -> Query {
+        let topology = self.topology;
+        self.filter(|entity| distance(topology, entity, center) <= radius)
+    }
+
+    /// The entity closest to `from`, the lowest id on ties.
+    pub fn nearest(self, from: Coordinate) -> Option<Entity> {
+        let topology = self.topology;
+        self.entities
+            .into_iter()
+            .min_by_key(|entity| distance(topology, entity, from))
+    }
+
+    pub fn first(self) -> Option<Entity> {
+        self.entities.into_iter().next()
+    }
+
+    pub fn count(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn collect(self) -> Vec<Entity> {
+        self.entities
+    }
+}
+
+/// Steps from `coord` to the closest tile `entity` covers.
+fn distance(topology: &dyn Topology, entity: &Entity, coord: Coordinate) -> i32 {
+    entity
+        .cells()
+        .into_iter()
+        .map(|cell| topology.distance(cell, coord))
+        .min()
+        .unwrap_or(i32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use models::coordinate::Coordinate;
+    use models::entity::EntityType;
+    use models::rules::Rules;
+    use models::view::WorldView;
+    use serializers::basic;
+
+    #[test]
+    fn find_players_wherever_they_were_loaded() {
+        let world = basic::load("#$2 1");
+
+        assert_eq!(
+            world.find_player(1).unwrap().coord,
+            Coordinate::new(4.0, 0.0)
+        );
+        assert_eq!(world.find_player(2).unwrap().id, 3);
+        assert!(world.find_player(3).is_none());
+        assert_eq!(world.query().players().count(), 2);
+    }
+
+    #[test]
+    fn of_type() {
+        let world = basic::load("$#$~#");
+        let mountains = world.query().of_type(EntityType::Obstacle('#')).collect();
+
+        assert_eq!(mountains.len(), 2);
+        assert_eq!(mountains[1].coord, Coordinate::new(4.0, 0.0));
+    }
+
+    #[test]
+    fn in_rect() {
+        let world = basic::load(&["$  $", " $  ", "$  $"].join("\n"));
+        let inside = world
+            .query()
+            .in_rect(Coordinate::new(0.0, 0.0), Coordinate::new(1.0, 1.0));
+
+        assert_eq!(inside.count(), 2);
+    }
+
+    #[test]
+    fn within_covers_footprints() {
+        let world = basic::load(&["1   HH", "    HH"].join("\n"));
+        let from = Coordinate::new(0.0, 0.0);
+
+        assert_eq!(world.query().within(from, 3).count(), 1);
+        assert_eq!(world.query().within(from, 4).count(), 2);
+    }
+
+    #[test]
+    fn nearest() {
+        let world = basic::load(&["$   1  $", "     $  "].join("\n"));
+        let from = world.find_player(1).unwrap().coord;
+        let treasure = world.query().filter(|entity| entity.is_treasure());
+
+        assert_eq!(
+            treasure.clone().nearest(from).unwrap().coord,
+            Coordinate::new(5.0, 1.0)
+        );
+        assert!(treasure.filter(|_| false).nearest(from).is_none());
+    }
+
+    #[test]
+    fn views_only_query_what_they_see() {
+        let mut world = basic::load(&["1 $", "   ", "   ", "   ", "  2"].join("\n"));
+        world.set_rules(Rules {
+            sight_radius: 2,
+            ..Default::default()
+        });
+        world.update_visibility();
+        let view = WorldView::for_player(&world, 1);
+
+        assert!(view.find_player(1).is_some());
+        assert!(view.find_player(2).is_none());
+        assert_eq!(view.query().count(), 2);
+    }
+}
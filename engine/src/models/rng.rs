@@ -0,0 +1,217 @@
+/// Seedable pseudo random number generator (SplitMix64). Small and fast,
+/// with the same sequence on every platform, so games can be replayed from
+/// their seed and action log.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Rng {
+    seed: u64,
+    state: u64,
+}
+
+pub const DEFAULT_SEED: u64 = 0;
+
+impl Default for Rng {
+    fn default() -> Rng {
+        Rng::new(DEFAULT_SEED)
+    }
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { seed, state: seed }
+    }
+
+    /// Restores a generator that already produced some numbers.
+    pub fn from_state(seed: u64, state: u64) -> Rng {
+        Rng { seed, state }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Returns a number in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Returns a uniformly distributed number in `[0, n)`.
+    pub fn below(&mut self, n: u32) -> u32 {
+        assert!(n > 0, "Can't pick a number below 0");
+
+        // Reject the top values that would make the modulo biased
+        let limit = u32::MAX - u32::MAX % n;
+        loop {
+            let value = self.next_u32();
+            if value < limit {
+                return value % n;
+            }
+        }
+    }
+
+    /// Returns a uniformly distributed number in `[low, high)`.
+    pub fn range(&mut self, low: i32, high: i32) -> i32 {
+        assert!(low < high, "Empty range: {}..{}", low, high);
+        let span = (i64::from(high) - i64::from(low)) as u32;
+        (i64::from(low) + i64::from(self.below(span))) as i32
+    }
+
+    /// Returns `true` with the given probability.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+
+    /// Returns an index picked with probability proportional to its weight,
+    /// or `None` if all weights are zero.
+    pub fn weighted_index(&mut self, weights: &[u32]) -> Option<usize> {
+        let total: u64 = weights.iter().map(|w| u64::from(*w)).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut target = self.next_u64() % total;
+        for (index, weight) in weights.iter().enumerate() {
+            let weight = u64::from(*weight);
+            if target < weight {
+                return Some(index);
+            }
+            target -= weight;
+        }
+
+        None
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+
+        let index = self.below(items.len() as u32) as usize;
+        items.get(index)
+    }
+
+    /// Fisher-Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u32 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_sequence() {
+        let mut rng = Rng::new(1234);
+        assert_eq!(rng.next_u64(), 0xbb0c_f61b_2f18_1cdb);
+        assert_eq!(rng.next_u64(), 0x97c7_a136_4df0_6524);
+    }
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut first = Rng::new(42);
+        let mut second = Rng::new(42);
+        let mut other = Rng::new(43);
+
+        let a: Vec<u64> = (0..16).map(|_| first.next_u64()).collect();
+        let b: Vec<u64> = (0..16).map(|_| second.next_u64()).collect();
+        let c: Vec<u64> = (0..16).map(|_| other.next_u64()).collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn restore_from_state() {
+        let mut rng = Rng::new(7);
+        rng.next_u64();
+
+        let mut restored = Rng::from_state(rng.seed(), rng.state());
+        assert_eq!(rng.next_u64(), restored.next_u64());
+        assert_eq!(restored.seed(), 7);
+    }
+
+    #[test]
+    fn range() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            let value = rng.range(-3, 4);
+            assert!((-3..4).contains(&value));
+        }
+
+        let mut seen = [false; 7];
+        for _ in 0..1000 {
+            seen[(rng.range(-3, 4) + 3) as usize] = true;
+        }
+        assert!(seen.iter().all(|s| *s));
+
+        assert_eq!(rng.range(5, 6), 5);
+        let extreme = rng.range(i32::MIN, i32::MAX);
+        assert!(extreme < i32::MAX);
+    }
+
+    #[test]
+    fn next_f32() {
+        let mut rng = Rng::new(2);
+        for _ in 0..1000 {
+            let value = rng.next_f32();
+            assert!((0.0..1.0).contains(&value));
+        }
+
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+    }
+
+    #[test]
+    fn weighted_index() {
+        let mut rng = Rng::new(3);
+        assert_eq!(rng.weighted_index(&[]), None);
+        assert_eq!(rng.weighted_index(&[0, 0]), None);
+        assert_eq!(rng.weighted_index(&[0, 5, 0]), Some(1));
+
+        let mut counts = [0; 2];
+        for _ in 0..1000 {
+            counts[rng.weighted_index(&[1, 3]).unwrap()] += 1;
+        }
+        assert!(counts[1] > counts[0] * 2);
+    }
+
+    #[test]
+    fn choose() {
+        let mut rng = Rng::new(4);
+        let empty: [i32; 0] = [];
+        assert_eq!(rng.choose(&empty), None);
+        assert_eq!(rng.choose(&[9]), Some(&9));
+    }
+
+    #[test]
+    fn shuffle() {
+        let mut items: Vec<i32> = (0..20).collect();
+        Rng::new(5).shuffle(&mut items);
+
+        let mut again: Vec<i32> = (0..20).collect();
+        Rng::new(5).shuffle(&mut again);
+        assert_eq!(items, again);
+
+        assert_ne!(items, (0..20).collect::<Vec<i32>>());
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<i32>>());
+    }
+}
@@ -0,0 +1,111 @@
+use actions::shooting;
+use models::coordinate::Coordinate;
+use models::direction::Direction;
+use models::fov;
+use models::topology::{Hex, Square4, Square8, Topology};
+
+/// Which neighbouring tiles an entity can step to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Connectivity {
+    Four,
+    Eight,
+    Hex,
+}
+
+/// Rule settings of a game, as read from a rules file by
+/// `serializers::rules`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rules {
+    pub connectivity: Connectivity,
+    /// Whether diagonal moves may squeeze between a mountain and the tile
+    /// next to it.
+    pub corner_cutting: bool,
+    /// How many pushables in a row a single move can shove. None can be
+    /// pushed at 0.
+    pub push_limit: usize,
+    /// How many tiles away entities see.
+    pub sight_radius: i32,
+    /// How many tiles away shots reach.
+    pub shot_range: i32,
+    /// Chance of a shot hitting a target in the open.
+    pub hit_chance: f32,
+    /// How much lower the chance is for a target in cover.
+    pub cover_bonus: f32,
+    /// Health a hit takes off its target.
+    pub damage: i32,
+    /// Edge of the worlds made without a map. Maps keep their own size.
+    pub board: Coordinate,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            connectivity: Connectivity::Four,
+            corner_cutting: false,
+            push_limit: 1,
+            sight_radius: fov::SIGHT_RADIUS,
+            shot_range: shooting::RANGE,
+            hit_chance: shooting::HIT_CHANCE,
+            cover_bonus: shooting::COVER_BONUS,
+            damage: shooting::DAMAGE,
+            board: Coordinate::new(8.0, 4.0),
+        }
+    }
+}
+
+impl Rules {
+    pub fn eight_way(corner_cutting: bool) -> Rules {
+        Rules {
+            connectivity: Connectivity::Eight,
+            corner_cutting,
+            ..Default::default()
+        }
+    }
+
+    pub fn hex() -> Rules {
+        Rules {
+            connectivity: Connectivity::Hex,
+            ..Default::default()
+        }
+    }
+
+    pub fn topology(&self) -> &'static dyn Topology {
+        match self.connectivity {
+            Connectivity::Four => &Square4,
+            Connectivity::Eight => &Square8,
+            Connectivity::Hex => &Hex,
+        }
+    }
+
+    /// Directions entities may move in.
+    pub fn directions(&self) -> &'static [Direction] {
+        self.topology().directions()
+    }
+
+    pub fn allows(&self, dir: Direction) -> bool {
+        self.directions().contains(&dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::direction;
+
+    #[test]
+    fn allowed_directions() {
+        let four = Rules::default();
+        assert!(four.allows(direction::UP));
+        assert!(!four.allows(direction::UP_LEFT));
+        assert!(!four.allows(Direction { dx: 2.0, dy: 0.0 }));
+
+        let eight = Rules::eight_way(false);
+        assert!(eight.allows(direction::UP_LEFT));
+        assert_eq!(eight.directions().len(), 8);
+
+        let hex = Rules::hex();
+        assert!(hex.allows(direction::UP_RIGHT));
+        assert!(!hex.allows(direction::DOWN_RIGHT));
+        assert_eq!(hex.directions().len(), 6);
+    }
+}
@@ -0,0 +1,268 @@
+use models::coordinate::Coordinate;
+use models::direction::{self, Direction};
+
+/// Shape of the grid: which tiles neighbour each other, how far apart tiles
+/// are and which tiles a straight line crosses.
+///
+/// Hex grids use axial coordinates, `x` being the column `q` and `y` the row
+/// `r`, so a rectangular world is a rhombus of hexes.
+pub trait Topology {
+    /// Unit steps to every neighbour, clockwise.
+    fn directions(&self) -> &'static [Direction];
+
+    /// Number of steps between two tiles.
+    fn distance(&self, from: Coordinate, to: Coordinate) -> i32;
+
+    /// Tiles crossed going straight from `from` to `to`, both included.
+    fn line(&self, from: Coordinate, to: Coordinate) -> Vec<Coordinate>;
+
+    fn step(&self, coord: Coordinate, dir: Direction) -> Coordinate {
+        coord.translate(dir.dx, dir.dy)
+    }
+
+    fn neighbours(&self, coord: Coordinate) -> Vec<Coordinate> {
+        self.directions()
+            .iter()
+            .map(|dir| self.step(coord, *dir))
+            .collect()
+    }
+
+    /// Tiles next to each other, or the same tile.
+    fn is_adjacent(&self, a: Coordinate, b: Coordinate) -> bool {
+        self.distance(a, b) <= 1
+    }
+
+    /// Tiles a step from `from` squeezes between, which may block it.
+    fn corners(&self, _from: Coordinate, _dir: Direction) -> Vec<Coordinate> {
+        vec![]
+    }
+}
+
+fn tile(coord: Coordinate) -> (i32, i32) {
+    (coord.x.round() as i32, coord.y.round() as i32)
+}
+
+fn coordinate((x, y): (i32, i32)) -> Coordinate {
+    Coordinate::new(x as f32, y as f32)
+}
+
+/// Tiles crossed by a straight line on a square grid, using Bresenham's
+/// algorithm.
+pub fn bresenham(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+    let (mut x, mut y) = from;
+    let dx = (to.0 - x).abs();
+    let dy = -(to.1 - y).abs();
+    let step_x = if x < to.0 { 1 } else { -1 };
+    let step_y = if y < to.1 { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    let mut tiles = vec![(x, y)];
+    while (x, y) != to {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+        tiles.push((x, y));
+    }
+
+    tiles
+}
+
+fn square_line(from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
+    bresenham(tile(from), tile(to))
+        .into_iter()
+        .map(coordinate)
+        .collect()
+}
+
+/// Square tiles, moving up, down, left and right.
+pub struct Square4;
+
+impl Topology for Square4 {
+    fn directions(&self) -> &'static [Direction] {
+        &direction::ALL
+    }
+
+    fn distance(&self, from: Coordinate, to: Coordinate) -> i32 {
+        let (a, b) = (tile(from), tile(to));
+        (a.0 - b.0).abs() + (a.1 - b.1).abs()
+    }
+
+    fn line(&self, from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
+        square_line(from, to)
+    }
+}
+
+/// Square tiles with diagonal moves.
+pub struct Square8;
+
+impl Topology for Square8 {
+    fn directions(&self) -> &'static [Direction] {
+        &direction::ALL_EIGHT
+    }
+
+    fn distance(&self, from: Coordinate, to: Coordinate) -> i32 {
+        let (a, b) = (tile(from), tile(to));
+        (a.0 - b.0).abs().max((a.1 - b.1).abs())
+    }
+
+    fn line(&self, from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
+        square_line(from, to)
+    }
+
+    fn corners(&self, from: Coordinate, dir: Direction) -> Vec<Coordinate> {
+        if dir.is_diagonal() {
+            vec![from.translate(dir.dx, 0.0), from.translate(0.0, dir.dy)]
+        } else {
+            vec![]
+        }
+    }
+}
+
+/// Pointy topped hexes in axial coordinates.
+pub struct Hex;
+
+pub const HEX_DIRECTIONS: [Direction; 6] = [
+    Direction { dx: 1.0, dy: -1.0 },
+    Direction { dx: 1.0, dy: 0.0 },
+    Direction { dx: 0.0, dy: 1.0 },
+    Direction { dx: -1.0, dy: 1.0 },
+    Direction { dx: -1.0, dy: 0.0 },
+    Direction { dx: 0.0, dy: -1.0 },
+];
+
+/// Rounds fractional cube coordinates to the hex containing them.
+fn cube_round(q: f32, r: f32) -> (i32, i32) {
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+
+    (rq as i32, rr as i32)
+}
+
+impl Topology for Hex {
+    fn directions(&self) -> &'static [Direction] {
+        &HEX_DIRECTIONS
+    }
+
+    fn distance(&self, from: Coordinate, to: Coordinate) -> i32 {
+        let (a, b) = (tile(from), tile(to));
+        let (dq, dr) = (a.0 - b.0, a.1 - b.1);
+        (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+    }
+
+    fn line(&self, from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
+        let steps = self.distance(from, to);
+        if steps == 0 {
+            return vec![coordinate(tile(from))];
+        }
+
+        // Nudged so lines running along hex edges pick a consistent side
+        let (a, b) = (tile(from), tile(to));
+        let (aq, ar) = (a.0 as f32 + 1e-3, a.1 as f32 + 1e-3);
+        let (bq, br) = (b.0 as f32 + 1e-3, b.1 as f32 + 1e-3);
+
+        (0..=steps)
+            .map(|i| {
+                let t = i as f32 / steps as f32;
+                coordinate(cube_round(aq + (bq - aq) * t, ar + (br - ar) * t))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: i32, y: i32) -> Coordinate {
+        Coordinate::new(x as f32, y as f32)
+    }
+
+    #[test]
+    fn neighbours() {
+        assert_eq!(Square4.neighbours(at(0, 0)).len(), 4);
+        assert_eq!(Square8.neighbours(at(0, 0)).len(), 8);
+
+        let hexes = Hex.neighbours(at(2, 2));
+        assert_eq!(hexes.len(), 6);
+        assert!(hexes.iter().all(|h| Hex.distance(at(2, 2), *h) == 1));
+        assert!(!hexes.contains(&at(3, 3)));
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(Square4.distance(at(0, 0), at(3, 2)), 5);
+        assert_eq!(Square8.distance(at(0, 0), at(3, 2)), 3);
+        assert_eq!(Hex.distance(at(0, 0), at(3, 2)), 5);
+        assert_eq!(Hex.distance(at(0, 0), at(3, -2)), 3);
+        assert_eq!(Hex.distance(at(1, 1), at(1, 1)), 0);
+    }
+
+    #[test]
+    fn adjacency() {
+        assert!(Square4.is_adjacent(at(0, 0), at(0, 0)));
+        assert!(!Square4.is_adjacent(at(0, 0), at(1, 1)));
+        assert!(Square8.is_adjacent(at(0, 0), at(1, 1)));
+        assert!(!Square8.is_adjacent(at(-1, 0), at(0, 5)));
+        assert!(Hex.is_adjacent(at(0, 0), at(1, -1)));
+        assert!(!Hex.is_adjacent(at(0, 0), at(1, 1)));
+    }
+
+    #[test]
+    fn bresenham_lines() {
+        assert_eq!(bresenham((0, 0), (0, 0)), vec![(0, 0)]);
+        assert_eq!(
+            bresenham((0, 0), (3, 0)),
+            vec![(0, 0), (1, 0), (2, 0), (3, 0)]
+        );
+        assert_eq!(bresenham((0, 0), (2, 2)), vec![(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(
+            bresenham((4, 1), (0, 0)),
+            vec![(4, 1), (3, 1), (2, 0), (1, 0), (0, 0)]
+        );
+        assert_eq!(
+            bresenham((0, 3), (1, 0)),
+            vec![(0, 3), (0, 2), (1, 1), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn hex_lines() {
+        assert_eq!(Hex.line(at(0, 0), at(0, 0)), vec![at(0, 0)]);
+        assert_eq!(
+            Hex.line(at(0, 0), at(3, 0)),
+            vec![at(0, 0), at(1, 0), at(2, 0), at(3, 0)]
+        );
+
+        let line = Hex.line(at(0, 0), at(2, 2));
+        assert_eq!(line.len(), 5);
+        assert_eq!(line[0], at(0, 0));
+        assert_eq!(line[4], at(2, 2));
+        for pair in line.windows(2) {
+            assert_eq!(Hex.distance(pair[0], pair[1]), 1);
+        }
+    }
+
+    #[test]
+    fn square_corners() {
+        assert!(Square4.corners(at(1, 1), direction::DOWN_RIGHT).is_empty());
+        assert!(Square8.corners(at(1, 1), direction::DOWN).is_empty());
+        assert_eq!(
+            Square8.corners(at(1, 1), direction::DOWN_RIGHT),
+            vec![at(2, 1), at(1, 2)]
+        );
+        assert!(Hex.corners(at(1, 1), HEX_DIRECTIONS[0]).is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use actions::action::ActionData;
+use actions::processor;
+use models::coordinate::Coordinate;
+use models::entity::{Entity, EntityType};
+use models::owner::Owner;
+use models::query::Query;
+use models::rules::Rules;
+use models::world::World;
+
+const SNAPSHOT_SEED: u64 = 0;
+
+/// Read only view over a `World`, the only thing controllers get to see.
+///
+/// A view for a player only shows its own entities, whatever stands on the
+/// tiles it currently sees, and the terrain of tiles it explored before.
+#[derive(Clone, Copy)]
+pub struct WorldView<'a> {
+    world: &'a World,
+    player: Option<i8>,
+}
+
+impl<'a> WorldView<'a> {
+    /// Sees everything, for the engine and spectators.
+    pub fn new(world: &'a World) -> WorldView<'a> {
+        WorldView {
+            world,
+            player: None,
+        }
+    }
+
+    pub fn for_player(world: &'a World, player: i8) -> WorldView<'a> {
+        WorldView {
+            world,
+            player: Some(player),
+        }
+    }
+
+    /// What an entity owned by `owner` gets to see: only players are
+    /// limited by fog.
+    pub fn for_owner(world: &'a World, owner: Owner) -> WorldView<'a> {
+        match owner {
+            Owner::Player(player) => WorldView::for_player(world, player),
+            _ => WorldView::new(world),
+        }
+    }
+
+    pub fn player(&self) -> Option<i8> {
+        self.player
+    }
+
+    pub fn left_edge(&self) -> Coordinate {
+        self.world.left_edge
+    }
+
+    pub fn right_edge(&self) -> Coordinate {
+        self.world.right_edge
+    }
+
+    pub fn rules(&self) -> &'a Rules {
+        self.world.rules()
+    }
+
+    pub fn is_inside(&self, coord: Coordinate) -> bool {
+        coord.is_within(self.world.left_edge, self.world.right_edge)
+    }
+
+    pub fn is_visible(&self, coord: Coordinate) -> bool {
+        match self.player {
+            Some(player) => self
+                .world
+                .visibility(player)
+                .is_some_and(|visibility| visibility.is_visible(coord)),
+            None => true,
+        }
+    }
+
+    pub fn is_explored(&self, coord: Coordinate) -> bool {
+        match self.player {
+            Some(player) => self
+                .world
+                .visibility(player)
+                .is_some_and(|visibility| visibility.is_explored(coord)),
+            None => true,
+        }
+    }
+
+    fn sees(&self, entity: &Entity) -> bool {
+        let player = match self.player {
+            Some(player) => player,
+            None => return true,
+        };
+
+        match entity.entity_type {
+            _ if entity.owner == Owner::Player(player) => true,
+            _ if entity.terrain.is_some() => entity
+                .cells()
+                .into_iter()
+                .any(|cell| self.is_explored(cell)),
+            _ => entity.cells().into_iter().any(|cell| self.is_visible(cell)),
+        }
+    }
+
+    pub fn get_entity(&self, entity_id: i32) -> Option<Entity> {
+        self.world
+            .get_entity(entity_id)
+            .filter(|entity| self.sees(entity))
+    }
+
+    pub fn on_coord(&self, coord: Coordinate) -> Option<Entity> {
+        self.entities().find(|entity| entity.occupies(coord))
+    }
+
+    /// Iterates over the entities in id order.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + 'a {
+        let view = *self;
+        self.world
+            .entities()
+            .filter(move |entity| view.sees(entity))
+    }
+
+    /// Looks up the entities this view sees.
+    pub fn query(&self) -> Query {
+        Query::new(self.entities(), self.rules().topology())
+    }
+
+    /// The entity playing for player `number`, if it's in sight.
+    pub fn find_player(&self, number: i8) -> Option<Entity> {
+        self.query().of_type(EntityType::Player(number)).first()
+    }
+
+    /// A copy of the world, without what this view can't see, to simulate
+    /// on: other players' memory of the map is left out, and the dice it
+    /// rolls aren't the ones the world will roll.
+    pub fn snapshot(&self) -> World {
+        let mut world = self.world.clone();
+        world.retain_entities(|entity| self.sees(entity));
+        if let Some(player) = self.player {
+            world.retain_visibility(|other| other == player);
+        }
+        world.reseed(SNAPSHOT_SEED);
+        world
+    }
+
+    pub fn legal_actions(&self, entity_id: i32) -> Vec<ActionData> {
+        match self.player {
+            Some(_) => processor::legal_actions(&self.snapshot(), entity_id),
+            None => processor::legal_actions(self.world, entity_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::actors::{mountain, player};
+    use serializers::basic;
+
+    #[test]
+    fn sees_the_world() {
+        let mut world = World::create(Coordinate::new(3.0, 3.0));
+        let player = world.register(player::create_at(1, Coordinate::new(1.0, 1.0)));
+        world.register(mountain::create_at(Coordinate::new(2.0, 1.0)));
+
+        let view = WorldView::new(&world);
+        assert!(view.is_inside(Coordinate::new(3.0, 3.0)));
+        assert!(!view.is_inside(Coordinate::new(4.0, 3.0)));
+        assert_eq!(view.get_entity(player.id).unwrap().id, player.id);
+        assert_eq!(view.on_coord(Coordinate::new(2.0, 1.0)).unwrap().id, 2);
+        assert_eq!(view.entities().count(), 2);
+        assert_eq!(view.legal_actions(player.id).len(), 3);
+    }
+
+    #[test]
+    fn fog_of_war() {
+        let mut world = basic::load(&["1 #  2", "      ", "    # "].join("\n"));
+        let view = WorldView::for_player(&world, 1);
+
+        // Player 2 hides behind the mountain
+        assert!(view.get_entity(1).is_some());
+        assert!(view.get_entity(3).is_none());
+        assert!(!view.is_visible(Coordinate::new(5.0, 0.0)));
+        assert!(view.is_visible(Coordinate::new(5.0, 1.0)));
+        assert_eq!(view.entities().count(), 3);
+        assert_eq!(view.snapshot().entity_count(), 3);
+
+        // Terrain stays on the map once explored, players don't
+        world.update_entity(
+            world
+                .get_entity(1)
+                .unwrap()
+                .with_coordinate(Coordinate::new(4.0, 1.0)),
+        );
+        world.update_visibility();
+        world.update_entity(
+            world
+                .get_entity(1)
+                .unwrap()
+                .with_coordinate(Coordinate::new(0.0, 2.0)),
+        );
+        world.update_entity(
+            world
+                .get_entity(3)
+                .unwrap()
+                .with_coordinate(Coordinate::new(5.0, 2.0)),
+        );
+        world.update_visibility();
+
+        let view = WorldView::for_player(&world, 1);
+        assert!(!view.is_visible(Coordinate::new(5.0, 2.0)));
+        assert!(view.is_explored(Coordinate::new(5.0, 2.0)));
+        assert!(view.get_entity(4).is_some());
+        assert!(view.get_entity(3).is_none());
+
+        let spectator = WorldView::new(&world);
+        assert_eq!(spectator.entities().count(), 4);
+        assert!(spectator.get_entity(3).is_some());
+    }
+
+    #[test]
+    fn snapshots_hide_what_isnt_seen() {
+        let mut world = basic::load(&["1 #  2", "      "].join("\n"));
+        world.reseed(7);
+        world.rng_mut().next_u64();
+
+        let snapshot = WorldView::for_player(&world, 1).snapshot();
+        assert!(snapshot.visibility(1).is_some());
+        assert!(snapshot.visibility(2).is_none());
+        assert_ne!(snapshot.rng().state(), world.rng().state());
+
+        let spectator = WorldView::new(&world).snapshot();
+        assert!(spectator.visibility(2).is_some());
+        assert_ne!(spectator.rng().state(), world.rng().state());
+    }
+
+    #[test]
+    fn legal_actions_ignore_unseen_entities() {
+        let world = basic::load(&["1#2", "   "].join("\n"));
+        let view = WorldView::for_player(&world, 2);
+        assert!(view.get_entity(1).is_none());
+        assert_eq!(view.legal_actions(3).len(), 1);
+        assert_eq!(
+            WorldView::for_owner(&world, Owner::Engine)
+                .entities()
+                .count(),
+            3
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::BTreeSet;
+
+use models::coordinate::Coordinate;
+use models::fov::{self, Tile};
+
+/// What a player sees right now, and every tile it has ever seen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Visibility {
+    visible: BTreeSet<Tile>,
+    explored: BTreeSet<Tile>,
+}
+
+impl Visibility {
+    pub fn new() -> Visibility {
+        Default::default()
+    }
+
+    /// Replaces the visible tiles, remembering them as explored.
+    pub fn update(&mut self, visible: BTreeSet<Tile>) {
+        self.explored.extend(visible.iter().cloned());
+        self.visible = visible;
+    }
+
+    pub fn is_visible(&self, coord: Coordinate) -> bool {
+        self.visible.contains(&fov::tile(coord))
+    }
+
+    pub fn is_explored(&self, coord: Coordinate) -> bool {
+        self.explored.contains(&fov::tile(coord))
+    }
+
+    pub fn visible(&self) -> &BTreeSet<Tile> {
+        &self.visible
+    }
+
+    pub fn explored(&self) -> &BTreeSet<Tile> {
+        &self.explored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remembers_explored_tiles() {
+        let mut visibility = Visibility::new();
+        visibility.update(vec![(0, 0), (1, 0)].into_iter().collect());
+        visibility.update(vec![(1, 0), (2, 0)].into_iter().collect());
+
+        assert!(!visibility.is_visible(Coordinate::new(0.0, 0.0)));
+        assert!(visibility.is_explored(Coordinate::new(0.0, 0.0)));
+        assert!(visibility.is_visible(Coordinate::new(2.0, 0.0)));
+        assert!(!visibility.is_explored(Coordinate::new(3.0, 0.0)));
+        assert_eq!(visibility.explored().len(), 3);
+    }
+}
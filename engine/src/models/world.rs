@@ -1,16 +1,32 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use actions::action::ActionData;
+use actions::handler::{ActionHandler, ActionHandlers};
+use game::victory::Victory;
+use models::checksum;
+use models::components::Components;
 use models::coordinate::Coordinate;
-use models::entity::Entity;
+use models::entity::{Entity, EntityType};
+use models::fov;
+use models::owner::Owner;
+use models::query::Query;
+use models::rng::Rng;
+use models::rules::Rules;
+use models::visibility::Visibility;
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct World {
     current_id: i32,
     pub left_edge: Coordinate,
     pub right_edge: Coordinate,
-    pub entities: HashMap<i32, Entity>,
+    components: Components,
     actions: Vec<ActionData>,
+    handlers: ActionHandlers,
+    rng: Rng,
+    rules: Rules,
+    victory: Vec<Victory>,
+    turn: u32,
+    visibility: BTreeMap<i8, Visibility>,
 }
 
 impl Default for World {
@@ -18,9 +34,15 @@ impl Default for World {
         World {
             current_id: 0,
             left_edge: Coordinate::new(0.0, 0.0),
-            right_edge: Coordinate::new(8.0, 4.0),
-            entities: HashMap::new(),
+            right_edge: Rules::default().board,
+            components: Default::default(),
             actions: vec![],
+            handlers: ActionHandlers::builtin(),
+            rng: Default::default(),
+            rules: Default::default(),
+            victory: vec![],
+            turn: 0,
+            visibility: BTreeMap::new(),
         }
     }
 }
@@ -33,6 +55,15 @@ impl World {
         }
     }
 
+    /// An empty world playing by `rules`, as big as their board.
+    pub fn with_rules(rules: Rules) -> World {
+        World {
+            right_edge: rules.board,
+            rules,
+            ..Default::default()
+        }
+    }
+
     pub fn create(edge: Coordinate) -> World {
         World {
             right_edge: edge,
@@ -40,22 +71,208 @@ impl World {
         }
     }
 
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    pub fn rng(&self) -> &Rng {
+        &self.rng
+    }
+
+    /// The world's random number generator. Only engine code should draw
+    /// from it, so games can be replayed from the seed and action log.
+    pub fn rng_mut(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    pub fn restore_rng(&mut self, rng: Rng) {
+        self.rng = rng;
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
+    }
+
+    /// Handlers processing each kind of action, moving and shooting unless
+    /// more were registered.
+    pub fn handlers(&self) -> &ActionHandlers {
+        &self.handlers
+    }
+
+    pub fn handlers_mut(&mut self) -> &mut ActionHandlers {
+        &mut self.handlers
+    }
+
+    /// Lets the world process actions of `handler`'s kind, replacing the
+    /// handler it had for them.
+    pub fn register_handler<H: ActionHandler + 'static>(&mut self, handler: H) {
+        self.handlers.register(handler);
+    }
+
+    /// Conditions ending the game, in order of precedence.
+    pub fn victory(&self) -> &[Victory] {
+        &self.victory
+    }
+
+    pub fn set_victory(&mut self, victory: Vec<Victory>) {
+        self.victory = victory;
+    }
+
+    /// Turns processed so far.
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn end_turn(&mut self) {
+        self.turn += 1;
+    }
+
+    /// Looks up entities by what they are or where they stand.
+    pub fn query(&self) -> Query {
+        Query::new(self.entities(), self.rules.topology())
+    }
+
+    /// The entity playing for player `number`.
+    pub fn find_player(&self, number: i8) -> Option<Entity> {
+        self.query().of_type(EntityType::Player(number)).first()
+    }
+
+    pub fn current_id(&self) -> i32 {
+        self.current_id
+    }
+
+    /// Returns a checksum of the whole state, stable across runs and
+    /// machines, used to detect desyncs.
+    pub fn checksum(&self) -> u64 {
+        checksum::of_world(self)
+    }
+
+    /// What `player` sees and has explored, as of the last
+    /// `update_visibility`.
+    pub fn visibility(&self, player: i8) -> Option<&Visibility> {
+        self.visibility.get(&player)
+    }
+
+    /// What every player sees, by player number.
+    pub fn visibilities(&self) -> &BTreeMap<i8, Visibility> {
+        &self.visibility
+    }
+
+    /// Forgets what every player `keep` returns `false` for sees.
+    pub fn retain_visibility<F>(&mut self, keep: F)
+    where
+        F: Fn(i8) -> bool,
+    {
+        self.visibility.retain(|player, _| keep(*player));
+    }
+
+    pub fn restore_visibility(&mut self, player: i8, visibility: Visibility) {
+        self.visibility.insert(player, visibility);
+    }
+
+    /// Recomputes what every player sees from the entities it owns.
+    pub fn update_visibility(&mut self) {
+        let mut seen: BTreeMap<i8, BTreeSet<fov::Tile>> = BTreeMap::new();
+        for player in self.visibility.keys() {
+            seen.insert(*player, BTreeSet::new());
+        }
+
+        for (id, controller) in self.components.controllers.iter() {
+            if let (Owner::Player(player), Some(position)) =
+                (controller.owner, self.components.positions.get(id))
+            {
+                let visible = fov::visible_from(self, position.coord, self.rules.sight_radius);
+                seen.entry(player).or_default().extend(visible);
+            }
+        }
+
+        for (player, visible) in seen {
+            self.visibility.entry(player).or_default().update(visible);
+        }
+    }
+
+    /// The component stores, what actions and systems read and change an
+    /// entity through, one kind of component at a time.
+    pub fn components(&self) -> &Components {
+        &self.components
+    }
+
+    pub fn components_mut(&mut self) -> &mut Components {
+        &mut self.components
+    }
+
+    /// Iterates over the entities in id order, so it's deterministic.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.components
+            .ids()
+            .filter_map(move |id| self.components.get(id))
+    }
+
+    pub fn entity_count(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Removes every entity `keep` returns `false` for.
+    pub fn retain_entities<F>(&mut self, keep: F)
+    where
+        F: Fn(&Entity) -> bool,
+    {
+        let removed: Vec<i32> = self
+            .entities()
+            .filter(|entity| !keep(entity))
+            .map(|entity| entity.id)
+            .collect();
+        for id in removed {
+            self.components.remove(id);
+        }
+    }
+
     pub fn get_entity(&self, entity_id: i32) -> Option<Entity> {
-        self.entities.get(&entity_id).cloned()
+        self.components.get(entity_id)
     }
 
-    pub fn on_coord(&self, coord: Coordinate) -> Option<&Entity> {
-        self.entities.values().find(|entity| entity.coord == coord)
+    /// Returns the entity covering the given coordinate. If several share
+    /// it, the one with the lowest id wins.
+    pub fn on_coord(&self, coord: Coordinate) -> Option<Entity> {
+        self.components
+            .positions
+            .iter()
+            .find(|(_, position)| position.occupies(coord))
+            .and_then(|(id, _)| self.get_entity(id))
     }
 
+    /// Returns the entity trailing right behind `leader_id`, if any.
+    pub fn follower(&self, leader_id: i32) -> Option<Entity> {
+        self.components
+            .controllers
+            .iter()
+            .find(|(_, controller)| controller.leader == Some(leader_id))
+            .and_then(|(id, _)| self.get_entity(id))
+    }
+
+    /// Replaces every component of `entity.id`, as loaders set whole
+    /// entities. Actions and systems change single components through
+    /// `components_mut` instead.
     pub fn update_entity(&mut self, entity: Entity) {
-        self.entities.insert(entity.id, entity);
+        self.components.insert(&entity);
+    }
+
+    pub fn remove_entity(&mut self, entity_id: i32) -> Option<Entity> {
+        self.components.remove(entity_id)
     }
 
     pub fn register(&mut self, entity: Entity) -> Entity {
         self.current_id += 1;
         let new_entity: Entity = entity.with_id(self.current_id);
-        self.entities.insert(self.current_id, new_entity);
+        self.components.insert(&new_entity);
         new_entity
     }
 
@@ -80,6 +297,7 @@ impl World {
 mod tests {
     use super::*;
     use actions::movement;
+    use models::actors::{player, town, wagon};
     use models::coordinate::Coordinate;
 
     #[test]
@@ -92,7 +310,113 @@ mod tests {
 
         let entity2 = world.register(Entity::new(0, Coordinate::new(0.0, 0.0)));
         assert_eq!(2, entity2.id);
-        assert_eq!(entity2.id, world.entities[&entity2.id].id);
+        assert_eq!(world.entity_count(), 2);
+    }
+
+    #[test]
+    fn reseed() {
+        let mut world: World = World::new();
+        world.reseed(99);
+        assert_eq!(world.seed(), 99);
+
+        let mut other = world.clone();
+        assert_eq!(world.rng_mut().next_u64(), other.rng_mut().next_u64());
+    }
+
+    #[test]
+    fn sized_by_rules() {
+        let rules = Rules {
+            board: Coordinate::new(12.0, 6.0),
+            ..Default::default()
+        };
+        let world = World::with_rules(rules);
+        assert_eq!(world.right_edge, Coordinate::new(12.0, 6.0));
+        assert_eq!(*world.rules(), rules);
+        assert_eq!(World::new().right_edge, Coordinate::new(8.0, 4.0));
+    }
+
+    #[test]
+    fn sight_comes_from_the_rules() {
+        let mut world = World::create(Coordinate::new(8.0, 1.0));
+        world.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+        world.set_rules(Rules {
+            sight_radius: 2,
+            ..Default::default()
+        });
+        world.update_visibility();
+
+        let visibility = world.visibility(1).unwrap();
+        assert!(visibility.is_visible(Coordinate::new(2.0, 0.0)));
+        assert!(!visibility.is_visible(Coordinate::new(3.0, 0.0)));
+    }
+
+    #[test]
+    fn iterate_by_id() {
+        let mut world: World = World::new();
+        for x in 0..32 {
+            world.register(Entity::new(0, Coordinate::new(x as f32, 0.0)));
+        }
+
+        let ids: Vec<i32> = world.entities().map(|entity| entity.id).collect();
+        assert_eq!(ids, (1..33).collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn on_coord_prefers_lowest_id() {
+        let mut world: World = World::new();
+        let coord = Coordinate::new(1.0, 1.0);
+        let first = world.register(Entity::new(0, coord));
+        world.register(Entity::new(0, coord));
+
+        assert_eq!(world.on_coord(coord).unwrap().id, first.id);
+    }
+
+    #[test]
+    fn on_coord_covers_footprint() {
+        let mut world: World = World::new();
+        let town = world.register(town::create_at(Coordinate::new(1.0, 1.0)));
+
+        assert_eq!(
+            world.on_coord(Coordinate::new(2.0, 2.0)).unwrap().id,
+            town.id
+        );
+        assert!(world.on_coord(Coordinate::new(3.0, 2.0)).is_none());
+    }
+
+    #[test]
+    fn follower() {
+        let mut world: World = World::new();
+        let player = world.register(player::create_at(1, Coordinate::new(1.0, 0.0)));
+        let wagon = world.register(wagon::create_behind(&player, Coordinate::new(0.0, 0.0)));
+
+        assert_eq!(world.follower(player.id).unwrap().id, wagon.id);
+        assert_eq!(wagon.owner, player.owner);
+        assert!(world.follower(wagon.id).is_none());
+    }
+
+    #[test]
+    fn update_visibility() {
+        let mut world: World = World::create(Coordinate::new(20.0, 0.0));
+        let entity = world.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+        assert!(world.visibility(1).is_none());
+
+        world.update_visibility();
+        let visibility = world.visibility(1).unwrap();
+        assert!(visibility.is_visible(Coordinate::new(8.0, 0.0)));
+        assert!(!visibility.is_visible(Coordinate::new(9.0, 0.0)));
+        assert!(world.visibility(2).is_none());
+
+        world.update_entity(entity.with_coordinate(Coordinate::new(12.0, 0.0)));
+        world.update_visibility();
+        let visibility = world.visibility(1).unwrap();
+        assert!(!visibility.is_visible(Coordinate::new(0.0, 0.0)));
+        assert!(visibility.is_explored(Coordinate::new(0.0, 0.0)));
+        assert!(visibility.is_visible(Coordinate::new(20.0, 0.0)));
+
+        world.remove_entity(entity.id);
+        world.update_visibility();
+        assert!(world.visibility(1).unwrap().visible().is_empty());
+        assert_eq!(world.visibility(1).unwrap().explored().len(), 21);
     }
 
     #[test]
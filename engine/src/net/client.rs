@@ -0,0 +1,80 @@
+use std::io::{BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use actions::action::ActionData;
+use net::error::{ProtocolError, ProtocolResult};
+use net::protocol::{self, ClientMessage, ServerMessage, VERSION};
+
+/// Connection to a game server. Server messages are read on a background
+/// thread so frontends can poll them without blocking their frame loop.
+pub struct Client {
+    pub player: i8,
+    writer: TcpStream,
+    messages: Receiver<ServerMessage>,
+}
+
+impl Client {
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> ProtocolResult<Client> {
+        Client::connect_with_version(addr, VERSION)
+    }
+
+    pub fn connect_with_version<A: ToSocketAddrs>(addr: A, version: u32) -> ProtocolResult<Client> {
+        let mut writer = TcpStream::connect(addr)?;
+        let mut reader = BufReader::new(writer.try_clone()?);
+
+        writer.write_all(protocol::encode_client(&ClientMessage::Hello(version)).as_bytes())?;
+
+        let player = match protocol::read_server(&mut reader)? {
+            ServerMessage::Welcome { player, .. } => player,
+            ServerMessage::Error(reason) => return Err(ProtocolError::Refused(reason)),
+            other => return Err(ProtocolError::Malformed(format!("{:?}", other))),
+        };
+
+        let (sender, messages) = mpsc::channel();
+        thread::spawn(move || {
+            while let Ok(message) = protocol::read_server(&mut reader) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Client {
+            player,
+            writer,
+            messages,
+        })
+    }
+
+    pub fn submit(&mut self, action: ActionData) -> ProtocolResult<()> {
+        self.send(&ClientMessage::Action(action))
+    }
+
+    pub fn quit(&mut self) -> ProtocolResult<()> {
+        self.send(&ClientMessage::Quit)
+    }
+
+    fn send(&mut self, message: &ClientMessage) -> ProtocolResult<()> {
+        self.writer
+            .write_all(protocol::encode_client(message).as_bytes())?;
+        Ok(())
+    }
+
+    /// Blocks until the next server message arrives.
+    pub fn receive(&self) -> ProtocolResult<ServerMessage> {
+        self.messages
+            .recv()
+            .map_err(|_| ProtocolError::Disconnected)
+    }
+
+    /// Returns the next server message, if one already arrived.
+    pub fn try_receive(&self) -> ProtocolResult<Option<ServerMessage>> {
+        match self.messages.try_recv() {
+            Ok(message) => Ok(Some(message)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(ProtocolError::Disconnected),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::fmt;
+use std::io;
+
+/// An enum containing all kinds of network protocol errors.
+#[derive(Debug)]
+pub enum ProtocolError {
+    Io(io::Error),
+    Malformed(String),
+    UnsupportedVersion(u32),
+    Refused(String),
+    Disconnected,
+}
+
+pub type ProtocolResult<T> = std::result::Result<T, ProtocolError>;
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProtocolError::Io(error) => write!(f, "IO error: {}", error),
+            ProtocolError::Malformed(line) => write!(f, "Malformed message: {}", line),
+            ProtocolError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported protocol version: {}", version)
+            }
+            ProtocolError::Refused(reason) => write!(f, "Connection refused: {}", reason),
+            ProtocolError::Disconnected => write!(f, "Peer disconnected"),
+        }
+    }
+}
+
+impl From<io::Error> for ProtocolError {
+    fn from(error: io::Error) -> ProtocolError {
+        ProtocolError::Io(error)
+    }
+}
@@ -0,0 +1,7 @@
+pub mod client;
+pub mod error;
+pub mod protocol;
+pub mod server;
+
+pub use self::client::Client;
+pub use self::server::Server;
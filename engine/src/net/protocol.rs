@@ -0,0 +1,746 @@
+//! Line based text protocol spoken between the game server and its clients.
+//!
+//! Every message is a single line, except `STATE` which carries the turn,
+//! the world edges and its rules (topology, corner cutting, push limit, sight
+//! radius, shot range, hit chance, cover bonus and damage), and is followed
+//! by one `ENTITY` line per entity (id, position, type, owner, gold, ammo,
+//! footprint, the entity it trails behind, health as `<current>/<max>` and
+//! terrain as `<OPEN|OPAQUE>/<SOLID|PASSABLE>`, `-` standing for none) and
+//! terminated by `END`. Players are only sent the entities they can see.
+//! Once the game is over the server sends `GAMEOVER WON <player>` or
+//! `GAMEOVER DRAW`.
+//! A session goes like:
+//!
+//! ```text
+//! C: HELLO 13
+//! S: WELCOME 13 2
+//! S: STATE 0 0 0 7 1 FOUR 0 1 8 6 0.7 0.3 1
+//! S: ENTITY 1 0 0 PLAYER 1 P1 0 6 1 1 - 1/1 -
+//! S: ENTITY 2 6 1 OBSTACLE # ENGINE 0 0 1 1 - - OPAQUE/SOLID
+//! S: END
+//! C: ACTION 1 MOVE 0 1
+//! C: ACTION 1 SHOOT 3
+//! S: STATE 1 0 0 7 1 FOUR 0 1 8 6 0.7 0.3 1
+//! ...
+//! ```
+//!
+//! Actions are `ACTION <entity> <KIND> <payload>`, the kind being any the
+//! server's world has a handler for and the payload `-`, a target id, a
+//! direction `<dx> <dy>` or `*` followed by the numbers an action added by a
+//! mod carries. They don't carry their issuer: the server issues them on
+//! behalf of the player bound to the connection.
+use std::io::BufRead;
+use std::str::FromStr;
+
+use actions::action::{ActionData, Payload, Values};
+use actions::handler::ActionHandlers;
+use game::victory::GameState;
+use models::components::{Health, Terrain};
+use models::coordinate::Coordinate;
+use models::direction::Direction;
+use models::entity::{Entity, EntityType, Footprint};
+use models::inventory::Inventory;
+use models::owner::Owner;
+use models::rules::{Connectivity, Rules};
+use models::world::World;
+use net::error::{ProtocolError, ProtocolResult};
+
+/// Version of the wire protocol, exchanged on `HELLO` and `WELCOME`.
+pub const VERSION: u32 = 13;
+
+#[derive(Clone, Debug)]
+pub enum ClientMessage {
+    Hello(u32),
+    Action(ActionData),
+    Quit,
+}
+
+#[derive(Clone, Debug)]
+pub enum ServerMessage {
+    Welcome { version: u32, player: i8 },
+    State { turn: u32, world: Box<World> },
+    Rejected(String),
+    Error(String),
+    GameOver(GameState),
+}
+
+pub fn encode_client(message: &ClientMessage) -> String {
+    match message {
+        ClientMessage::Hello(version) => format!("HELLO {}\n", version),
+        ClientMessage::Action(action) => encode_action(action),
+        ClientMessage::Quit => "QUIT\n".to_string(),
+    }
+}
+
+fn encode_action(action: &ActionData) -> String {
+    let payload = match action.payload {
+        Payload::None => "-".to_string(),
+        Payload::Direction(dir) => format!("{} {}", dir.dx, dir.dy),
+        Payload::Target(target) => target.to_string(),
+        Payload::Values(values) => values
+            .as_slice()
+            .iter()
+            .fold("*".to_string(), |line, value| format!("{} {}", line, value)),
+    };
+    format!(
+        "ACTION {} {} {}\n",
+        action.entity_id,
+        action.kind.name().to_uppercase(),
+        payload
+    )
+}
+
+/// Decodes a client message, only accepting actions `handlers` can process.
+pub fn decode_client(line: &str, handlers: &ActionHandlers) -> ProtocolResult<ClientMessage> {
+    let mut tokens = line.split_whitespace();
+
+    match tokens.next() {
+        Some("HELLO") => Ok(ClientMessage::Hello(parse(tokens.next(), line)?)),
+        Some("ACTION") => decode_action(tokens, line, handlers),
+        Some("QUIT") => Ok(ClientMessage::Quit),
+        _ => Err(malformed(line)),
+    }
+}
+
+fn decode_action<'a, I>(
+    mut tokens: I,
+    line: &str,
+    handlers: &ActionHandlers,
+) -> ProtocolResult<ClientMessage>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let entity_id: i32 = parse(tokens.next(), line)?;
+    let kind = tokens
+        .next()
+        .and_then(|name| handlers.kind(name))
+        .ok_or_else(|| malformed(line))?;
+
+    let mut tokens = tokens.peekable();
+    if tokens.peek() == Some(&"*") {
+        let values = tokens
+            .skip(1)
+            .map(|value| parse(Some(value), line))
+            .collect::<ProtocolResult<Vec<i32>>>()?;
+        let values = Values::new(&values).ok_or_else(|| malformed(line))?;
+        return Ok(ClientMessage::Action(ActionData::new(
+            entity_id,
+            kind,
+            Payload::Values(values),
+        )));
+    }
+
+    let payload = match (tokens.next(), tokens.next()) {
+        (Some("-"), None) => Payload::None,
+        (Some(id), None) => Payload::Target(parse(Some(id), line)?),
+        (dx, dy) => Payload::Direction(Direction {
+            dx: parse(dx, line)?,
+            dy: parse(dy, line)?,
+        }),
+    };
+    if tokens.next().is_some() {
+        return Err(malformed(line));
+    }
+
+    Ok(ClientMessage::Action(ActionData::new(
+        entity_id, kind, payload,
+    )))
+}
+
+pub fn encode_server(message: &ServerMessage) -> String {
+    match message {
+        ServerMessage::Welcome { version, player } => format!("WELCOME {} {}\n", version, player),
+        ServerMessage::State { turn, world } => encode_state(*turn, world),
+        ServerMessage::Rejected(reason) => format!("REJECTED {}\n", reason),
+        ServerMessage::Error(reason) => format!("ERROR {}\n", reason),
+        ServerMessage::GameOver(state) => match state {
+            GameState::Won(player) => format!("GAMEOVER WON {}\n", player),
+            GameState::Draw => "GAMEOVER DRAW\n".to_string(),
+            GameState::Running => "GAMEOVER RUNNING\n".to_string(),
+        },
+    }
+}
+
+fn encode_state(turn: u32, world: &World) -> String {
+    let mut lines = vec![format!(
+        "STATE {} {} {} {} {} {}",
+        turn,
+        world.left_edge.x,
+        world.left_edge.y,
+        world.right_edge.x,
+        world.right_edge.y,
+        encode_rules(world.rules())
+    )];
+
+    for entity in world.entities() {
+        lines.push(format!(
+            "ENTITY {} {} {} {} {} {} {} {} {} {} {} {}",
+            entity.id,
+            entity.coord.x,
+            entity.coord.y,
+            encode_entity_type(entity.entity_type),
+            encode_owner(entity.owner),
+            entity.inventory.gold,
+            entity.inventory.ammo,
+            entity.footprint.width,
+            entity.footprint.height,
+            entity
+                .leader
+                .map_or_else(|| "-".to_string(), |leader| leader.to_string()),
+            entity.health.map_or_else(
+                || "-".to_string(),
+                |health| format!("{}/{}", health.current, health.max)
+            ),
+            encode_terrain(entity.terrain)
+        ));
+    }
+
+    lines.push("END\n".to_string());
+    lines.join("\n")
+}
+
+fn encode_terrain(terrain: Option<Terrain>) -> String {
+    match terrain {
+        Some(terrain) => format!(
+            "{}/{}",
+            if terrain.blocks_sight {
+                "OPAQUE"
+            } else {
+                "OPEN"
+            },
+            if terrain.passable {
+                "PASSABLE"
+            } else {
+                "SOLID"
+            }
+        ),
+        None => "-".to_string(),
+    }
+}
+
+fn encode_rules(rules: &Rules) -> String {
+    let connectivity = match rules.connectivity {
+        Connectivity::Four => "FOUR",
+        Connectivity::Eight => "EIGHT",
+        Connectivity::Hex => "HEX",
+    };
+    format!(
+        "{} {} {} {} {} {} {} {}",
+        connectivity,
+        rules.corner_cutting as u8,
+        rules.push_limit,
+        rules.sight_radius,
+        rules.shot_range,
+        rules.hit_chance,
+        rules.cover_bonus,
+        rules.damage
+    )
+}
+
+fn encode_entity_type(entity_type: EntityType) -> String {
+    match entity_type {
+        EntityType::Player(n) => format!("PLAYER {}", n),
+        EntityType::Enemy(c) => format!("ENEMY {}", c),
+        EntityType::Obstacle(c) => format!("OBSTACLE {}", c),
+        EntityType::Hole(c) => format!("HOLE {}", c),
+        EntityType::Treasure(c) => format!("TREASURE {}", c),
+        EntityType::Wagon(c) => format!("WAGON {}", c),
+        EntityType::Pushable(c) => format!("PUSHABLE {}", c),
+    }
+}
+
+fn encode_owner(owner: Owner) -> String {
+    match owner {
+        Owner::Engine => "ENGINE".to_string(),
+        Owner::Player(n) => format!("P{}", n),
+        Owner::Outlaws => "OUTLAWS".to_string(),
+    }
+}
+
+pub fn read_client<R: BufRead>(
+    reader: &mut R,
+    handlers: &ActionHandlers,
+) -> ProtocolResult<ClientMessage> {
+    decode_client(&read_line(reader)?, handlers)
+}
+
+pub fn read_server<R: BufRead>(reader: &mut R) -> ProtocolResult<ServerMessage> {
+    let line = read_line(reader)?;
+    let (keyword, rest) = split_keyword(&line);
+
+    match keyword {
+        "WELCOME" => {
+            let mut tokens = rest.split_whitespace();
+            Ok(ServerMessage::Welcome {
+                version: parse(tokens.next(), &line)?,
+                player: parse(tokens.next(), &line)?,
+            })
+        }
+        "STATE" => read_state(reader, &line),
+        "REJECTED" => Ok(ServerMessage::Rejected(rest.to_string())),
+        "ERROR" => Ok(ServerMessage::Error(rest.to_string())),
+        "GAMEOVER" => {
+            let mut tokens = rest.split_whitespace();
+            let state = match tokens.next() {
+                Some("WON") => GameState::Won(parse(tokens.next(), &line)?),
+                Some("DRAW") => GameState::Draw,
+                Some("RUNNING") => GameState::Running,
+                _ => return Err(malformed(&line)),
+            };
+            Ok(ServerMessage::GameOver(state))
+        }
+        _ => Err(malformed(&line)),
+    }
+}
+
+fn read_state<R: BufRead>(reader: &mut R, header: &str) -> ProtocolResult<ServerMessage> {
+    let mut tokens = header.split_whitespace().skip(1);
+    let turn: u32 = parse(tokens.next(), header)?;
+    let left_edge = Coordinate::new(parse(tokens.next(), header)?, parse(tokens.next(), header)?);
+    let right_edge = Coordinate::new(parse(tokens.next(), header)?, parse(tokens.next(), header)?);
+
+    let rules = decode_rules(&mut tokens, header)?;
+
+    let mut world = World::create(right_edge);
+    world.left_edge = left_edge;
+    world.set_rules(rules);
+
+    loop {
+        let line = read_line(reader)?;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("ENTITY") => {
+                let id: i32 = parse(tokens.next(), &line)?;
+                let coord =
+                    Coordinate::new(parse(tokens.next(), &line)?, parse(tokens.next(), &line)?);
+                let entity_type = decode_entity_type(tokens.next(), tokens.next(), &line)?;
+                let owner = decode_owner(tokens.next(), &line)?;
+                let inventory = Inventory {
+                    gold: parse(tokens.next(), &line)?,
+                    ammo: parse(tokens.next(), &line)?,
+                };
+                let footprint = Footprint {
+                    width: parse(tokens.next(), &line)?,
+                    height: parse(tokens.next(), &line)?,
+                };
+                let leader = match tokens.next() {
+                    Some("-") => None,
+                    token => Some(parse(token, &line)?),
+                };
+                let health = decode_health(tokens.next(), &line)?;
+                let terrain = decode_terrain(tokens.next(), &line)?;
+                world.update_entity(Entity {
+                    id,
+                    coord,
+                    entity_type,
+                    owner,
+                    inventory,
+                    footprint,
+                    leader,
+                    health,
+                    terrain,
+                });
+            }
+            Some("END") => {
+                return Ok(ServerMessage::State {
+                    turn,
+                    world: Box::new(world),
+                })
+            }
+            _ => return Err(malformed(&line)),
+        }
+    }
+}
+
+fn decode_health(token: Option<&str>, line: &str) -> ProtocolResult<Option<Health>> {
+    match token.map(|token| token.split('/').collect::<Vec<_>>()) {
+        Some(ref parts) if parts[..] == ["-"] => Ok(None),
+        Some(parts) if parts.len() == 2 => Ok(Some(Health {
+            current: parse(Some(parts[0]), line)?,
+            max: parse(Some(parts[1]), line)?,
+        })),
+        _ => Err(malformed(line)),
+    }
+}
+
+fn decode_terrain(token: Option<&str>, line: &str) -> ProtocolResult<Option<Terrain>> {
+    let parts: Vec<&str> = token.map_or(vec![], |token| token.split('/').collect());
+    let blocks_sight = match parts.as_slice() {
+        ["-"] => return Ok(None),
+        ["OPEN", _] => false,
+        ["OPAQUE", _] => true,
+        _ => return Err(malformed(line)),
+    };
+    let passable = match parts[1] {
+        "SOLID" => false,
+        "PASSABLE" => true,
+        _ => return Err(malformed(line)),
+    };
+
+    Ok(Some(Terrain {
+        blocks_sight,
+        passable,
+    }))
+}
+
+fn decode_rules<'a, I>(tokens: &mut I, line: &str) -> ProtocolResult<Rules>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let connectivity = match tokens.next() {
+        Some("FOUR") => Connectivity::Four,
+        Some("EIGHT") => Connectivity::Eight,
+        Some("HEX") => Connectivity::Hex,
+        _ => return Err(malformed(line)),
+    };
+    let corner_cutting: u8 = parse(tokens.next(), line)?;
+
+    Ok(Rules {
+        connectivity,
+        corner_cutting: corner_cutting != 0,
+        push_limit: parse(tokens.next(), line)?,
+        sight_radius: parse(tokens.next(), line)?,
+        shot_range: parse(tokens.next(), line)?,
+        hit_chance: parse(tokens.next(), line)?,
+        cover_bonus: parse(tokens.next(), line)?,
+        damage: parse(tokens.next(), line)?,
+        ..Default::default()
+    })
+}
+
+fn decode_entity_type(
+    kind: Option<&str>,
+    payload: Option<&str>,
+    line: &str,
+) -> ProtocolResult<EntityType> {
+    let glyph = || -> ProtocolResult<char> { parse(payload, line) };
+
+    match kind {
+        Some("PLAYER") => Ok(EntityType::Player(parse(payload, line)?)),
+        Some("ENEMY") => Ok(EntityType::Enemy(glyph()?)),
+        Some("OBSTACLE") => Ok(EntityType::Obstacle(glyph()?)),
+        Some("HOLE") => Ok(EntityType::Hole(glyph()?)),
+        Some("TREASURE") => Ok(EntityType::Treasure(glyph()?)),
+        Some("WAGON") => Ok(EntityType::Wagon(glyph()?)),
+        Some("PUSHABLE") => Ok(EntityType::Pushable(glyph()?)),
+        _ => Err(malformed(line)),
+    }
+}
+
+fn decode_owner(token: Option<&str>, line: &str) -> ProtocolResult<Owner> {
+    match token {
+        Some("ENGINE") => Ok(Owner::Engine),
+        Some("OUTLAWS") => Ok(Owner::Outlaws),
+        Some(raw) if raw.starts_with('P') => Ok(Owner::Player(parse(Some(&raw[1..]), line)?)),
+        _ => Err(malformed(line)),
+    }
+}
+
+fn read_line<R: BufRead>(reader: &mut R) -> ProtocolResult<String> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(ProtocolError::Disconnected);
+    }
+
+    Ok(line.trim_end().to_string())
+}
+
+fn split_keyword(line: &str) -> (&str, &str) {
+    match line.find(' ') {
+        Some(index) => (&line[..index], &line[index + 1..]),
+        None => (line, ""),
+    }
+}
+
+fn parse<T: FromStr>(token: Option<&str>, line: &str) -> ProtocolResult<T> {
+    token
+        .and_then(|raw| raw.parse().ok())
+        .ok_or_else(|| malformed(line))
+}
+
+fn malformed(line: &str) -> ProtocolError {
+    ProtocolError::Malformed(line.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions::action::ActionKind;
+    use actions::handler::ActionHandler;
+    use actions::result::ActionResult;
+    use actions::{movement, shooting};
+    use models::actors::{gold, mountain, player, town, wagon};
+    use std::io::Cursor;
+
+    fn decode(line: &str) -> ProtocolResult<ClientMessage> {
+        decode_client(line, &ActionHandlers::builtin())
+    }
+
+    #[test]
+    fn hello() {
+        let message = encode_client(&ClientMessage::Hello(VERSION));
+        assert_eq!(message, "HELLO 13\n");
+
+        match decode(&message) {
+            Ok(ClientMessage::Hello(version)) => assert_eq!(version, VERSION),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn action() {
+        let message = encode_client(&ClientMessage::Action(movement::up(3)));
+        assert_eq!(message, "ACTION 3 MOVE 0 -1\n");
+
+        match decode(&message) {
+            Ok(ClientMessage::Action(action)) => {
+                assert_eq!(action.entity_id, 3);
+                assert_eq!(action.kind, movement::MOVE);
+                let dir = action.direction().unwrap();
+                assert_eq!(Coordinate::new(dir.dx, dir.dy), Coordinate::new(0.0, -1.0));
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn shoot() {
+        let message = encode_client(&ClientMessage::Action(shooting::at(3, 7)));
+        assert_eq!(message, "ACTION 3 SHOOT 7\n");
+
+        match decode(&message) {
+            Ok(ClientMessage::Action(action)) => {
+                assert_eq!(action.entity_id, 3);
+                assert_eq!(action.kind, shooting::SHOOT);
+                assert_eq!(action.target(), Some(7));
+                assert!(action.direction().is_none());
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+        assert!(decode("ACTION 3 SHOOT x").is_err());
+    }
+
+    #[test]
+    fn action_without_direction() {
+        match decode("ACTION 3 MOVE -") {
+            Ok(ClientMessage::Action(action)) => assert_eq!(action.payload, Payload::None),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    struct Jump;
+
+    impl ActionHandler for Jump {
+        fn kind(&self) -> ActionKind {
+            ActionKind("jump")
+        }
+
+        fn validate(&self, _world: &World, _action: &ActionData) -> ActionResult<()> {
+            Ok(())
+        }
+
+        fn process(&self, _world: &mut World, _action: &ActionData) -> ActionResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn registered_actions() {
+        let mut handlers = ActionHandlers::builtin();
+        handlers.register(Jump);
+
+        let jump = ActionData::new(3, ActionKind("jump"), Payload::None);
+        let message = encode_client(&ClientMessage::Action(jump));
+        assert_eq!(message, "ACTION 3 JUMP -\n");
+        assert!(decode(&message).is_err());
+
+        match decode_client(&message, &handlers) {
+            Ok(ClientMessage::Action(action)) => assert_eq!(action.kind, ActionKind("jump")),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn actions_with_values() {
+        let mut handlers = ActionHandlers::builtin();
+        handlers.register(Jump);
+
+        let values = Values::new(&[2, -10]).unwrap();
+        let jump = ActionData::new(3, ActionKind("jump"), Payload::Values(values));
+        let message = encode_client(&ClientMessage::Action(jump));
+        assert_eq!(message, "ACTION 3 JUMP * 2 -10\n");
+
+        match decode_client(&message, &handlers) {
+            Ok(ClientMessage::Action(action)) => assert_eq!(action.values(), Some(&[2, -10][..])),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+        match decode_client("ACTION 3 JUMP *", &handlers) {
+            Ok(ClientMessage::Action(action)) => assert_eq!(action.values(), Some(&[][..])),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+        assert!(decode_client("ACTION 3 JUMP * 1 x", &handlers).is_err());
+        assert!(decode_client("ACTION 3 JUMP * 1 2 3 4 5", &handlers).is_err());
+    }
+
+    #[test]
+    fn malformed_client_message() {
+        assert!(decode("ACTION x MOVE 0 1").is_err());
+        assert!(decode("ACTION 1 JUMP 0 1").is_err());
+        assert!(decode("ACTION 1 MOVE").is_err());
+        assert!(decode("ACTION 1 MOVE 0 1 2").is_err());
+        assert!(decode("DANCE").is_err());
+    }
+
+    #[test]
+    fn welcome() {
+        let message = ServerMessage::Welcome {
+            version: VERSION,
+            player: 2,
+        };
+        let raw = encode_server(&message);
+        assert_eq!(raw, "WELCOME 13 2\n");
+
+        match read_server(&mut Cursor::new(raw)) {
+            Ok(ServerMessage::Welcome { version, player }) => {
+                assert_eq!(version, VERSION);
+                assert_eq!(player, 2);
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn state() {
+        let mut world = World::create(Coordinate::new(7.0, 1.0));
+        world.register(player::create_at(1, Coordinate::new(0.0, 0.0)));
+        world.register(mountain::create_at(Coordinate::new(6.0, 1.0)));
+        world.register(gold::create_at(Coordinate::new(3.0, 0.0)));
+
+        let raw = encode_server(&ServerMessage::State {
+            turn: 4,
+            world: Box::new(world),
+        });
+        assert_eq!(
+            raw,
+            [
+                "STATE 4 0 0 7 1 FOUR 0 1 8 6 0.7 0.3 1",
+                "ENTITY 1 0 0 PLAYER 1 P1 0 6 1 1 - 1/1 -",
+                "ENTITY 2 6 1 OBSTACLE # ENGINE 0 0 1 1 - - OPAQUE/SOLID",
+                "ENTITY 3 3 0 TREASURE $ ENGINE 10 0 1 1 - - -",
+                "END\n",
+            ]
+            .join("\n")
+        );
+
+        match read_server(&mut Cursor::new(raw)) {
+            Ok(ServerMessage::State { turn, world }) => {
+                assert_eq!(turn, 4);
+                assert_eq!(world.right_edge, Coordinate::new(7.0, 1.0));
+                assert_eq!(world.entity_count(), 3);
+                assert_eq!(world.get_entity(3).unwrap().inventory.gold, 10);
+                assert_eq!(world.get_entity(1).unwrap().inventory.ammo, 6);
+
+                let mountain = world.get_entity(2).unwrap();
+                assert_eq!(mountain.coord, Coordinate::new(6.0, 1.0));
+                assert_eq!(mountain.entity_type, EntityType::Obstacle('#'));
+                assert_eq!(mountain.owner, Owner::Engine);
+                assert!(mountain.terrain.unwrap().blocks_sight);
+                assert_eq!(world.get_entity(1).unwrap().health, Some(Health::new(1)));
+                assert_eq!(world.get_entity(1).unwrap().owner, Owner::Player(1));
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn state_footprints_and_trains() {
+        let mut world = World::create(Coordinate::new(7.0, 1.0));
+        let player = world.register(player::create_at(1, Coordinate::new(1.0, 0.0)));
+        world.register(wagon::create_behind(&player, Coordinate::new(0.0, 0.0)));
+        world.register(town::create_at(Coordinate::new(4.0, 0.0)));
+
+        let raw = encode_server(&ServerMessage::State {
+            turn: 0,
+            world: Box::new(world),
+        });
+        assert!(raw.contains("ENTITY 2 0 0 WAGON = P1 0 0 1 1 1 - -\n"));
+        assert!(raw.contains("ENTITY 3 4 0 OBSTACLE H ENGINE 0 0 2 2 - - OPAQUE/SOLID\n"));
+
+        match read_server(&mut Cursor::new(raw)) {
+            Ok(ServerMessage::State { world, .. }) => {
+                assert_eq!(world.follower(1).unwrap().id, 2);
+                assert_eq!(world.on_coord(Coordinate::new(5.0, 1.0)).unwrap().id, 3);
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn state_rules() {
+        let mut world = World::create(Coordinate::new(3.0, 3.0));
+        let rules = Rules {
+            sight_radius: 5,
+            hit_chance: 0.5,
+            damage: 2,
+            ..Rules::eight_way(true)
+        };
+        world.set_rules(rules);
+
+        let raw = encode_server(&ServerMessage::State {
+            turn: 0,
+            world: Box::new(world),
+        });
+        assert!(raw.starts_with("STATE 0 0 0 3 3 EIGHT 1 1 5 6 0.5 0.3 2\n"));
+
+        match read_server(&mut Cursor::new(raw)) {
+            Ok(ServerMessage::State { world, .. }) => {
+                assert_eq!(*world.rules(), rules)
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+
+        match read_server(&mut Cursor::new("STATE 0 0 0 3 3 OCTAGON 0 1\n")) {
+            Err(ProtocolError::Malformed(_)) => (),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejected() {
+        let raw = encode_server(&ServerMessage::Rejected("Invalid entity: 9".to_string()));
+        assert_eq!(raw, "REJECTED Invalid entity: 9\n");
+
+        match read_server(&mut Cursor::new(raw)) {
+            Ok(ServerMessage::Rejected(reason)) => assert_eq!(reason, "Invalid entity: 9"),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn game_over() {
+        let raw = encode_server(&ServerMessage::GameOver(GameState::Won(2)));
+        assert_eq!(raw, "GAMEOVER WON 2\n");
+
+        match read_server(&mut Cursor::new(raw)) {
+            Ok(ServerMessage::GameOver(state)) => assert_eq!(state, GameState::Won(2)),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+        match read_server(&mut Cursor::new(encode_server(&ServerMessage::GameOver(
+            GameState::Draw,
+        )))) {
+            Ok(ServerMessage::GameOver(state)) => assert_eq!(state, GameState::Draw),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+        assert!(read_server(&mut Cursor::new("GAMEOVER LOST 1\n")).is_err());
+    }
+
+    #[test]
+    fn truncated_state() {
+        let raw =
+            "STATE 0 0 0 7 1 FOUR 0 1 8 6 0.7 0.3 1\nENTITY 1 0 0 PLAYER 1 P1 0 6 1 1 - 1/1 -\n";
+        match read_server(&mut Cursor::new(raw)) {
+            Err(ProtocolError::Disconnected) => (),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+}
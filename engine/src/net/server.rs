@@ -0,0 +1,492 @@
+use std::io::{BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+use game::mode::{Classic, GameMode};
+use game::runner;
+use game::victory::GameState;
+use models::owner::Owner;
+use models::view::WorldView;
+use models::world::World;
+use net::error::{ProtocolError, ProtocolResult};
+use net::protocol::{self, ClientMessage, ServerMessage, VERSION};
+
+struct Connection {
+    player: i8,
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    fn send(&mut self, message: &ServerMessage) -> ProtocolResult<()> {
+        self.writer
+            .write_all(protocol::encode_server(message).as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Authoritative game server: owns the `World` and runs one turn once every
+/// connected player has submitted an action.
+pub struct Server {
+    listener: TcpListener,
+    world: World,
+    connections: Vec<Connection>,
+    turn: u32,
+    mode: Box<dyn GameMode>,
+}
+
+impl Server {
+    /// Serves a classic game of `world`.
+    pub fn bind<A: ToSocketAddrs>(addr: A, world: World) -> ProtocolResult<Server> {
+        Server::bind_with(addr, world, Box::new(Classic))
+    }
+
+    /// Serves a game of `world` played by `mode`, with the actions it
+    /// registers.
+    pub fn bind_with<A: ToSocketAddrs>(
+        addr: A,
+        mut world: World,
+        mode: Box<dyn GameMode>,
+    ) -> ProtocolResult<Server> {
+        world.update_visibility();
+        mode.register_handlers(world.handlers_mut());
+        Ok(Server {
+            listener: TcpListener::bind(addr)?,
+            world,
+            connections: vec![],
+            turn: 0,
+            mode,
+        })
+    }
+
+    pub fn state(&self) -> GameState {
+        self.mode.check_victory(&self.world)
+    }
+
+    pub fn local_addr(&self) -> ProtocolResult<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    /// Blocks until `count` players completed the handshake, then sends
+    /// everyone the initial state.
+    pub fn accept_players(&mut self, count: usize) -> ProtocolResult<()> {
+        while self.connections.len() < count {
+            let (stream, _) = self.listener.accept()?;
+            match self.handshake(stream) {
+                Ok(connection) => self.connections.push(connection),
+                Err(error) => eprintln!("Handshake failed: {}", error),
+            }
+        }
+
+        self.broadcast_state()
+    }
+
+    fn handshake(&self, stream: TcpStream) -> ProtocolResult<Connection> {
+        let mut connection = Connection {
+            player: self.connections.len() as i8 + 1,
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        };
+
+        match protocol::read_client(&mut connection.reader, self.world.handlers())? {
+            ClientMessage::Hello(VERSION) => {
+                let player = connection.player;
+                connection.send(&ServerMessage::Welcome {
+                    version: VERSION,
+                    player,
+                })?;
+                Ok(connection)
+            }
+            ClientMessage::Hello(version) => {
+                let error = ProtocolError::UnsupportedVersion(version);
+                connection.send(&ServerMessage::Error(error.to_string()))?;
+                Err(error)
+            }
+            other => Err(ProtocolError::Malformed(format!("{:?}", other))),
+        }
+    }
+
+    /// Collects one action from each player, runs the turn and broadcasts
+    /// the resulting state, refused actions being reported to their sender
+    /// only, then the result once the game is over. Actions
+    /// are issued on behalf of the player bound to the connection, so clients
+    /// can only command their own entities. A malformed line or a second
+    /// `HELLO` is answered with `ERROR`, the player waiting this turn.
+    pub fn play_turn(&mut self) -> ProtocolResult<()> {
+        // Connection each registered action came from
+        let mut senders = vec![];
+        for (index, connection) in self.connections.iter_mut().enumerate() {
+            match protocol::read_client(&mut connection.reader, self.world.handlers()) {
+                Ok(ClientMessage::Action(action)) => {
+                    let issuer = Owner::Player(connection.player);
+                    self.world.register_action(action.with_issuer(issuer));
+                    senders.push(index);
+                }
+                Ok(ClientMessage::Quit) => return Err(ProtocolError::Disconnected),
+                Ok(ClientMessage::Hello(_)) => {
+                    connection.send(&ServerMessage::Error("Already connected".to_string()))?
+                }
+                Err(error @ ProtocolError::Malformed(_)) => {
+                    connection.send(&ServerMessage::Error(error.to_string()))?
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        match runner::run_turn(&mut self.world, self.mode.as_mut()) {
+            Ok(outcomes) => {
+                for (index, outcome) in senders.into_iter().zip(outcomes) {
+                    if let Err(error) = outcome {
+                        let rejected = ServerMessage::Rejected(error.to_string());
+                        self.connections[index].send(&rejected)?;
+                    }
+                }
+            }
+            Err(error) => self.broadcast(&ServerMessage::Rejected(error.to_string()))?,
+        }
+
+        self.turn += 1;
+        self.broadcast_state()?;
+
+        match self.state() {
+            GameState::Running => Ok(()),
+            state => self.broadcast(&ServerMessage::GameOver(state)),
+        }
+    }
+
+    /// Plays turns until a player leaves or the game is over.
+    pub fn run(&mut self) -> ProtocolResult<()> {
+        while self.state() == GameState::Running {
+            match self.play_turn() {
+                Ok(_) => (),
+                Err(ProtocolError::Disconnected) => return Ok(()),
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends every player the part of the world it can see.
+    fn broadcast_state(&mut self) -> ProtocolResult<()> {
+        for connection in &mut self.connections {
+            let view = WorldView::for_player(&self.world, connection.player);
+            connection.send(&ServerMessage::State {
+                turn: self.turn,
+                world: Box::new(view.snapshot()),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn broadcast(&mut self, message: &ServerMessage) -> ProtocolResult<()> {
+        for connection in &mut self.connections {
+            connection.send(message)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions::action::{ActionData, ActionKind, Payload};
+    use actions::handler::{ActionHandler, ActionHandlers};
+    use actions::movement;
+    use actions::result::ActionResult;
+    use game::victory::Victory;
+    use models::coordinate::Coordinate;
+    use net::client::Client;
+    use serializers::basic;
+    use std::thread;
+
+    fn start(players: usize, turns: u32) -> (SocketAddr, thread::JoinHandle<World>) {
+        let world = basic::load(&["1  ", "  2"].join("\n"));
+        let mut server = Server::bind("127.0.0.1:0", world).unwrap();
+        let addr = server.local_addr().unwrap();
+
+        let handle = thread::spawn(move || {
+            server.accept_players(players).unwrap();
+            for _ in 0..turns {
+                server.play_turn().unwrap();
+            }
+            server.world().clone()
+        });
+
+        (addr, handle)
+    }
+
+    fn expect_state(client: &mut Client) -> (u32, World) {
+        match client.receive().unwrap() {
+            ServerMessage::State { turn, world } => (turn, *world),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn play_a_turn_over_loopback() {
+        let (addr, handle) = start(2, 1);
+
+        let mut first = Client::connect(addr).unwrap();
+        let mut second = Client::connect(addr).unwrap();
+        assert_eq!(first.player, 1);
+        assert_eq!(second.player, 2);
+
+        assert_eq!(expect_state(&mut first).0, 0);
+        assert_eq!(expect_state(&mut second).0, 0);
+
+        first.submit(movement::down(1)).unwrap();
+        second.submit(movement::left(2)).unwrap();
+
+        let (turn, world) = expect_state(&mut first);
+        assert_eq!(turn, 1);
+        assert_eq!(
+            world.get_entity(1).unwrap().coord,
+            Coordinate::new(0.0, 1.0)
+        );
+        assert_eq!(
+            world.get_entity(2).unwrap().coord,
+            Coordinate::new(1.0, 1.0)
+        );
+
+        let (_, world) = expect_state(&mut second);
+        assert_eq!(
+            world.get_entity(2).unwrap().coord,
+            Coordinate::new(1.0, 1.0)
+        );
+
+        let server_world = handle.join().unwrap();
+        assert_eq!(
+            server_world.get_entity(1).unwrap().coord,
+            Coordinate::new(0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn rejected_actions_are_reported() {
+        let (addr, handle) = start(1, 1);
+
+        let mut client = Client::connect(addr).unwrap();
+        expect_state(&mut client);
+
+        client.submit(movement::up(1)).unwrap();
+
+        match client.receive().unwrap() {
+            ServerMessage::Rejected(reason) => {
+                assert_eq!(reason, "Coordinate is outside world: 0,-1")
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+        assert_eq!(expect_state(&mut client).0, 1);
+
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn clients_only_command_their_own_player() {
+        let (addr, handle) = start(2, 1);
+
+        let mut first = Client::connect(addr).unwrap();
+        let mut second = Client::connect(addr).unwrap();
+        expect_state(&mut first);
+        expect_state(&mut second);
+
+        first.submit(movement::down(2)).unwrap();
+        second.submit(movement::left(2)).unwrap();
+
+        match first.receive().unwrap() {
+            ServerMessage::Rejected(reason) => {
+                assert_eq!(reason, "Not allowed to command entity: 2")
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+
+        let (_, world) = expect_state(&mut first);
+        assert_eq!(
+            world.get_entity(2).unwrap().coord,
+            Coordinate::new(1.0, 1.0)
+        );
+        let (_, world) = expect_state(&mut second);
+        assert_eq!(
+            world.get_entity(2).unwrap().coord,
+            Coordinate::new(1.0, 1.0)
+        );
+
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn bad_lines_count_as_waiting() {
+        let mut world = basic::load(&["1 2", "   "].join("\n"));
+        world.set_victory(vec![Victory::Reach(Coordinate::new(1.0, 1.0))]);
+        let mut server = Server::bind("127.0.0.1:0", world).unwrap();
+        let addr = server.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            server.accept_players(2).unwrap();
+            server.play_turn().unwrap();
+            server.play_turn().unwrap();
+            server.world().clone()
+        });
+
+        let mut first = Client::connect(addr).unwrap();
+        let mut second = TcpStream::connect(addr).unwrap();
+        let mut reader = BufReader::new(second.try_clone().unwrap());
+        let hello = protocol::encode_client(&ClientMessage::Hello(VERSION));
+        second.write_all(hello.as_bytes()).unwrap();
+        let mut receive = || protocol::read_server(&mut reader).unwrap();
+        assert!(matches!(
+            receive(),
+            ServerMessage::Welcome { player: 2, .. }
+        ));
+        assert!(matches!(receive(), ServerMessage::State { turn: 0, .. }));
+        expect_state(&mut first);
+
+        first.submit(movement::down(1)).unwrap();
+        second.write_all(b"ACTION 2 JUMP\n").unwrap();
+        match receive() {
+            ServerMessage::Error(reason) => {
+                assert_eq!(reason, "Malformed message: ACTION 2 JUMP")
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+        assert!(matches!(receive(), ServerMessage::State { turn: 1, .. }));
+        expect_state(&mut first);
+
+        first.submit(movement::right(1)).unwrap();
+        second.write_all(hello.as_bytes()).unwrap();
+        match receive() {
+            ServerMessage::Error(reason) => assert_eq!(reason, "Already connected"),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+        assert!(matches!(receive(), ServerMessage::State { turn: 2, .. }));
+        match receive() {
+            ServerMessage::GameOver(state) => assert_eq!(state, GameState::Won(1)),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+
+        let world = handle.join().unwrap();
+        assert_eq!(world.turn(), 2);
+        assert_eq!(
+            world.get_entity(2).unwrap().coord,
+            Coordinate::new(2.0, 0.0)
+        );
+    }
+
+    const JUMP: ActionKind = ActionKind("jump");
+
+    /// Lands on the far side of the map.
+    struct Jump;
+
+    impl ActionHandler for Jump {
+        fn kind(&self) -> ActionKind {
+            JUMP
+        }
+
+        fn validate(&self, _world: &World, _action: &ActionData) -> ActionResult<()> {
+            Ok(())
+        }
+
+        fn process(&self, world: &mut World, action: &ActionData) -> ActionResult<()> {
+            let edge = world.right_edge;
+            if let Some(position) = world.components_mut().positions.get_mut(action.entity_id) {
+                position.coord = edge;
+            }
+            Ok(())
+        }
+    }
+
+    /// The first to jump to the far side wins.
+    struct Hopscotch;
+
+    impl GameMode for Hopscotch {
+        fn name(&self) -> &'static str {
+            "hopscotch"
+        }
+
+        fn register_handlers(&self, handlers: &mut ActionHandlers) {
+            handlers.register(Jump);
+        }
+
+        fn check_victory(&self, world: &World) -> GameState {
+            Victory::Reach(world.right_edge)
+                .decide(world)
+                .unwrap_or(GameState::Running)
+        }
+    }
+
+    #[test]
+    fn plays_by_its_mode() {
+        let world = basic::load(&["1  ", "   "].join("\n"));
+        let mut server = Server::bind_with("127.0.0.1:0", world, Box::new(Hopscotch)).unwrap();
+        let addr = server.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            server.accept_players(1).unwrap();
+            server.run().unwrap();
+            server.state()
+        });
+
+        let mut client = Client::connect(addr).unwrap();
+        expect_state(&mut client);
+        client
+            .submit(ActionData::new(1, JUMP, Payload::None))
+            .unwrap();
+        let (turn, world) = expect_state(&mut client);
+        assert_eq!(turn, 1);
+        assert_eq!(
+            world.get_entity(1).unwrap().coord,
+            Coordinate::new(2.0, 1.0)
+        );
+        match client.receive() {
+            Ok(ServerMessage::GameOver(state)) => assert_eq!(state, GameState::Won(1)),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+
+        assert_eq!(handle.join().unwrap(), GameState::Won(1));
+    }
+
+    #[test]
+    fn players_only_receive_what_they_see() {
+        let world = basic::load(&["1 #  2", "   #  "].join("\n"));
+        let mut server = Server::bind("127.0.0.1:0", world).unwrap();
+        let addr = server.local_addr().unwrap();
+        let handle = thread::spawn(move || server.accept_players(2).unwrap());
+
+        let mut first = Client::connect(addr).unwrap();
+        let mut second = Client::connect(addr).unwrap();
+        handle.join().unwrap();
+
+        let (_, world) = expect_state(&mut first);
+        assert!(world.get_entity(1).is_some());
+        assert!(world.get_entity(3).is_none());
+
+        let (_, world) = expect_state(&mut second);
+        assert!(world.get_entity(1).is_none());
+        assert!(world.get_entity(3).is_some());
+    }
+
+    #[test]
+    fn unsupported_version_is_refused() {
+        let (addr, handle) = start(1, 0);
+
+        match Client::connect_with_version(addr, VERSION + 1) {
+            Err(ProtocolError::Refused(_)) => (),
+            Err(error) => panic!("Unexpected error: {}", error),
+            Ok(_) => panic!("Expected the connection to be refused"),
+        }
+
+        let mut client = Client::connect(addr).unwrap();
+        assert_eq!(client.player, 1);
+        expect_state(&mut client);
+
+        handle.join().unwrap();
+    }
+}
@@ -1,8 +1,28 @@
-use models::actors::{bandid, mountain, player, water};
+use game::victory::Victory;
+use models::actors::wagon;
 use models::coordinate::Coordinate;
 use models::entity::{Entity, EntityType};
+use models::prefabs::Prefabs;
+use models::rng::Rng;
+use models::rules::{Connectivity, Rules};
 use models::world::World;
 
+/// Optional first line of a map, holding the RNG seed and, for saved games,
+/// its current state: `seed <seed> [<state>]`.
+const SEED_HEADER: &str = "seed ";
+
+/// Optional map line picking the grid: `topology <four|eight|hex> [corners]`,
+/// `corners` allowing diagonal moves to cut corners. Defaults to `four`.
+const TOPOLOGY_HEADER: &str = "topology ";
+
+/// Optional map line setting how many pushables a move can shove in a row:
+/// `push <limit>`. Defaults to 1.
+const PUSH_HEADER: &str = "push ";
+
+/// Optional map lines ending the game, checked in order: `victory gold <n>`,
+/// `victory last`, `victory richest <turns>` or `victory reach <x> <y>`.
+const VICTORY_HEADER: &str = "victory ";
+
 pub fn print(world: &World) -> String {
     let mut lines: Vec<String> = vec![];
     for y in 0..world.right_edge.y as i32 {
@@ -16,43 +36,177 @@ pub fn print(world: &World) -> String {
     lines.join("\n")
 }
 
+/// Same as `print`, prefixed by the RNG and rule headers so the game can be
+/// resumed.
+pub fn save(world: &World) -> String {
+    let mut headers = vec![format!(
+        "{}{} {}",
+        SEED_HEADER,
+        world.rng().seed(),
+        world.rng().state()
+    )];
+    let (rules, default) = (*world.rules(), Rules::default());
+    if (rules.connectivity, rules.corner_cutting) != (default.connectivity, default.corner_cutting)
+    {
+        headers.push(format!("{}{}", TOPOLOGY_HEADER, save_topology(rules)));
+    }
+    if rules.push_limit != default.push_limit {
+        headers.push(format!("{}{}", PUSH_HEADER, rules.push_limit));
+    }
+    for victory in world.victory() {
+        headers.push(format!("{}{}", VICTORY_HEADER, save_victory(*victory)));
+    }
+
+    format!("{}\n{}", headers.join("\n"), print(world))
+}
+
+fn save_topology(rules: Rules) -> String {
+    let connectivity = match rules.connectivity {
+        Connectivity::Four => "four",
+        Connectivity::Eight => "eight",
+        Connectivity::Hex => "hex",
+    };
+
+    if rules.corner_cutting {
+        format!("{} corners", connectivity)
+    } else {
+        connectivity.to_string()
+    }
+}
+
+fn save_victory(victory: Victory) -> String {
+    match victory {
+        Victory::Gold(gold) => format!("gold {}", gold),
+        Victory::LastStanding => "last".to_string(),
+        Victory::Richest(turns) => format!("richest {}", turns),
+        Victory::Reach(coord) => format!("reach {} {}", coord.x, coord.y),
+    }
+}
+
 fn coord_to_str(world: &World, x: f32, y: f32) -> String {
     match world.on_coord(Coordinate::new(x, y)) {
-        Some(entity) => match entity.entity_type {
-            EntityType::Player(n) => n.to_string(),
-            EntityType::Enemy(c) => c.to_string(),
-            EntityType::Obstacle(c) => c.to_string(),
-            EntityType::Hole(c) => c.to_string(),
-        },
+        Some(entity) => entity.entity_type.glyph().to_string(),
         None => " ".to_string(),
     }
 }
 
+/// Reads a map made of the built-in actors.
 pub fn load(raw: &str) -> World {
-    let lines: Vec<&str> = raw.split('\n').collect();
+    load_with(raw, Prefabs::builtin())
+}
 
-    let edge = Coordinate::new(lines[0].len() as f32, lines.len() as f32);
+/// Reads a map, each glyph spawning the actor `prefabs` draws with it.
+pub fn load_with(raw: &str, prefabs: &Prefabs) -> World {
+    let mut lines: Vec<&str> = raw.split('\n').collect();
+
+    let mut rng = None;
+    let mut rules = Rules::default();
+    let mut victory = vec![];
+    while let Some(raw) = lines.first().cloned() {
+        if raw.starts_with(SEED_HEADER) {
+            rng = Some(load_rng(raw));
+        } else if raw.starts_with(TOPOLOGY_HEADER) {
+            load_topology(raw, &mut rules);
+        } else if raw.starts_with(VICTORY_HEADER) {
+            victory.push(load_victory(raw));
+        } else if let Some(limit) = raw.strip_prefix(PUSH_HEADER) {
+            rules.push_limit = limit
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid push header `{}`", raw));
+        } else {
+            break;
+        }
+        lines.remove(0);
+    }
+
+    let width = lines.first().map_or(0, |line| line.len());
+    let edge = Coordinate::new(width as f32, lines.len() as f32);
     let mut world = World::create(edge.translate(-1.0, -1.0));
+    world.set_rules(rules);
+    world.set_victory(victory);
+    if let Some(rng) = rng {
+        world.restore_rng(rng);
+    }
 
     lines
         .iter()
         .enumerate()
-        .for_each(|(y, line)| load_line(&mut world, y as f32, &line));
+        .for_each(|(y, line)| load_line(&mut world, prefabs, y as f32, &line));
+    hitch_wagons(&mut world);
+    world.update_visibility();
 
     world
 }
 
-fn load_line(world: &mut World, y: f32, raw: &str) {
+fn load_rng(raw: &str) -> Rng {
+    let numbers: Vec<u64> = raw[SEED_HEADER.len()..]
+        .split_whitespace()
+        .map(|n| {
+            n.parse()
+                .unwrap_or_else(|_| panic!("Invalid seed header `{}`", raw))
+        })
+        .collect();
+
+    match numbers.as_slice() {
+        [seed] => Rng::new(*seed),
+        [seed, state] => Rng::from_state(*seed, *state),
+        _ => panic!("Invalid seed header `{}`", raw),
+    }
+}
+
+fn load_topology(raw: &str, rules: &mut Rules) {
+    let words: Vec<&str> = raw[TOPOLOGY_HEADER.len()..].split_whitespace().collect();
+    let (connectivity, corner_cutting) = match words.as_slice() {
+        [connectivity] => (*connectivity, false),
+        [connectivity, "corners"] => (*connectivity, true),
+        _ => panic!("Invalid topology header `{}`", raw),
+    };
+
+    let connectivity = match connectivity {
+        "four" => Connectivity::Four,
+        "eight" => Connectivity::Eight,
+        "hex" => Connectivity::Hex,
+        _ => panic!("Unknown topology `{}`", connectivity),
+    };
+
+    rules.connectivity = connectivity;
+    rules.corner_cutting = corner_cutting;
+}
+
+fn load_victory(raw: &str) -> Victory {
+    let words: Vec<&str> = raw[VICTORY_HEADER.len()..].split_whitespace().collect();
+    let number = |word: &str| {
+        word.parse()
+            .unwrap_or_else(|_| panic!("Invalid victory header `{}`", raw))
+    };
+
+    match words.as_slice() {
+        ["gold", gold] => Victory::Gold(number(gold) as i32),
+        ["last"] => Victory::LastStanding,
+        ["richest", turns] => Victory::Richest(number(turns) as u32),
+        ["reach", x, y] => Victory::Reach(Coordinate::new(number(x), number(y))),
+        _ => panic!("Invalid victory header `{}`", raw),
+    }
+}
+
+fn load_line(world: &mut World, prefabs: &Prefabs, y: f32, raw: &str) {
     raw.chars().enumerate().for_each(|(x, c)| {
         let coord = Coordinate::new(x as f32, y);
         let entity: Option<Entity> = match c {
-            '1' => Some(player::create_at(1, coord)),
-            '2' => Some(player::create_at(2, coord)),
-            'B' => Some(bandid::create_at(coord)),
-            '#' => Some(mountain::create_at(coord)),
-            '~' => Some(water::create_at(coord)),
             ' ' => None,
-            _ => panic!("Don't know how to handle `{}`", c),
+            // Only the top left tile of an actor covering several creates it
+            _ if world
+                .on_coord(coord)
+                .is_some_and(|entity| entity.entity_type.glyph() == c) =>
+            {
+                None
+            }
+            _ => Some(
+                prefabs
+                    .spawn_glyph(c, coord)
+                    .unwrap_or_else(|| panic!("Don't know how to handle `{}`", c)),
+            ),
         };
 
         if let Some(entity) = entity {
@@ -61,9 +215,35 @@ fn load_line(world: &mut World, y: f32, raw: &str) {
     });
 }
 
+/// Hitches wagons into trains behind each player, every wagon following
+/// the player or wagon next to it.
+fn hitch_wagons(world: &mut World) {
+    let players = world.query().players().collect();
+
+    for player in players {
+        let mut tail = player;
+        while let Some(loose) = loose_wagon_next_to(world, tail.coord) {
+            tail = wagon::hitch(&loose, &tail);
+            world.update_entity(tail);
+        }
+    }
+}
+
+fn loose_wagon_next_to(world: &World, coord: Coordinate) -> Option<Entity> {
+    let topology = world.rules().topology();
+    world.entities().find(|entity| {
+        matches!(entity.entity_type, EntityType::Wagon(_))
+            && entity.leader.is_none()
+            && topology.distance(entity.coord, coord) == 1
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use models::actors::{bandid, mountain, player, water};
+    use models::components::Health;
+    use models::prefabs::Prefab;
     use models::World;
 
     #[test]
@@ -96,6 +276,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn load_many_players() {
+        let world = load("1 2 3 4 5 6 7 8");
+        let players: Vec<EntityType> = world.entities().map(|entity| entity.entity_type).collect();
+
+        assert_eq!(players.len(), player::MAX as usize);
+        assert_eq!(players[2], EntityType::Player(3));
+        assert_eq!(players[7], EntityType::Player(8));
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_many_players() {
+        load("1 9");
+    }
+
+    #[test]
+    fn load_headers_only() {
+        let world = load("victory last");
+        assert_eq!(world.victory(), &[Victory::LastStanding]);
+        assert_eq!(world.entity_count(), 0);
+
+        assert_eq!(load("").entity_count(), 0);
+    }
+
+    #[test]
+    fn load_custom_actors() {
+        let mut prefabs = Prefabs::builtin().clone();
+        prefabs.register(Prefab {
+            health: Some(Health::new(3)),
+            ..Prefab::new("sheriff", EntityType::Enemy('S'))
+        });
+        let world = load_with("1 SY", &prefabs);
+
+        let sheriff = world.get_entity(2).unwrap();
+        assert_eq!(sheriff.entity_type, EntityType::Enemy('S'));
+        assert_eq!(sheriff.health, Some(Health::new(3)));
+        assert_eq!(
+            world.get_entity(3).unwrap().entity_type,
+            EntityType::Obstacle('Y')
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn unknown_actor() {
+        load("1 S");
+    }
+
     #[test]
     fn load_empty_world() {
         let world = load(&["        ", "        "].join("\n"));
@@ -107,40 +336,168 @@ mod tests {
 
     #[test]
     fn load_world_with_actors() {
-        let world = load(&["12      ", "     B#~"].join("\n"));
+        let world = load(&["12     $", "     B#~"].join("\n"));
 
         assert!(world.right_edge.is_at_x(7.0));
         assert!(world.right_edge.is_at_y(1.0));
         assert_eq!(world.has_actions(), false);
 
-        let player: Option<&Entity> = world.on_coord(Coordinate::new(0.0, 0.0));
+        let player: Option<Entity> = world.on_coord(Coordinate::new(0.0, 0.0));
         assert!(player.is_some());
         if let Some(entity) = player {
             assert_eq!(entity.entity_type, EntityType::Player(1));
         }
 
-        let player2: Option<&Entity> = world.on_coord(Coordinate::new(1.0, 0.0));
+        let player2: Option<Entity> = world.on_coord(Coordinate::new(1.0, 0.0));
         assert!(player2.is_some());
         if let Some(entity) = player2 {
             assert_eq!(entity.entity_type, EntityType::Player(2));
         }
 
-        let bandid: Option<&Entity> = world.on_coord(Coordinate::new(5.0, 1.0));
+        let bandid: Option<Entity> = world.on_coord(Coordinate::new(5.0, 1.0));
         assert!(bandid.is_some());
         if let Some(entity) = bandid {
             assert_eq!(entity.entity_type, EntityType::Enemy('B'));
         }
 
-        let mountain: Option<&Entity> = world.on_coord(Coordinate::new(6.0, 1.0));
+        let mountain: Option<Entity> = world.on_coord(Coordinate::new(6.0, 1.0));
         assert!(mountain.is_some());
         if let Some(entity) = mountain {
             assert_eq!(entity.entity_type, EntityType::Obstacle('#'));
         }
 
-        let water: Option<&Entity> = world.on_coord(Coordinate::new(7.0, 1.0));
+        let treasure: Option<Entity> = world.on_coord(Coordinate::new(7.0, 0.0));
+        assert!(treasure.is_some());
+        if let Some(entity) = treasure {
+            assert_eq!(entity.entity_type, EntityType::Treasure('$'));
+            assert_eq!(entity.inventory.gold, 10);
+        }
+
+        let water: Option<Entity> = world.on_coord(Coordinate::new(7.0, 1.0));
         assert!(water.is_some());
         if let Some(entity) = water {
             assert_eq!(entity.entity_type, EntityType::Hole('~'));
         }
     }
+
+    #[test]
+    fn load_town() {
+        let map = ["1HH ", " HH ", "  HH", "  HH"].join("\n");
+        let world = load(&map);
+
+        let towns: Vec<Entity> = world
+            .entities()
+            .filter(|entity| entity.entity_type == EntityType::Obstacle('H'))
+            .collect();
+        assert_eq!(towns.len(), 2);
+        assert_eq!(towns[0].coord, Coordinate::new(1.0, 0.0));
+        assert_eq!(towns[1].coord, Coordinate::new(2.0, 2.0));
+        assert_eq!(
+            world.on_coord(Coordinate::new(3.0, 3.0)).unwrap().id,
+            towns[1].id
+        );
+    }
+
+    #[test]
+    fn load_train() {
+        let map = ["  =1", "  = ", "   ="].join("\n");
+        let world = load(&map);
+
+        let player = world.on_coord(Coordinate::new(3.0, 0.0)).unwrap();
+        let first = world.follower(player.id).unwrap();
+        assert_eq!(first.coord, Coordinate::new(2.0, 0.0));
+        assert_eq!(first.owner, player.owner);
+        let second = world.follower(first.id).unwrap();
+        assert_eq!(second.coord, Coordinate::new(2.0, 1.0));
+        assert!(world.follower(second.id).is_none());
+
+        let loose = world.on_coord(Coordinate::new(3.0, 2.0)).unwrap();
+        assert_eq!(loose.leader, None);
+    }
+
+    #[test]
+    fn load_seed() {
+        let world = load(&["seed 42", "1  ", "  2"].join("\n"));
+
+        assert_eq!(world.seed(), 42);
+        assert!(world.right_edge.is_at_x(2.0));
+        assert!(world.right_edge.is_at_y(1.0));
+        assert_eq!(world.entity_count(), 2);
+
+        assert_eq!(load("   ").seed(), 0);
+    }
+
+    #[test]
+    fn save_rng_state() {
+        let mut world = load(&["seed 42", "1  "].join("\n"));
+        world.rng_mut().next_u64();
+
+        let saved = save(&world);
+        assert!(saved.starts_with(&format!("seed 42 {}\n", world.rng().state())));
+
+        let mut restored = load(&saved);
+        assert_eq!(restored.rng(), world.rng());
+        assert_eq!(restored.rng_mut().next_u64(), world.rng_mut().next_u64());
+    }
+
+    #[test]
+    fn load_topology() {
+        let world = load(&["topology hex", "seed 42", "1  "].join("\n"));
+        assert_eq!(*world.rules(), Rules::hex());
+        assert_eq!(world.seed(), 42);
+        assert!(world.right_edge.is_at_y(0.0));
+
+        let world = load(&["topology eight corners", "1  "].join("\n"));
+        assert_eq!(*world.rules(), Rules::eight_way(true));
+
+        assert_eq!(*load("1  ").rules(), Rules::default());
+    }
+
+    #[test]
+    fn save_topology() {
+        let mut world = load("1  ");
+        assert!(!save(&world).contains(TOPOLOGY_HEADER));
+        assert!(!save(&world).contains(PUSH_HEADER));
+
+        world.set_rules(Rules::eight_way(true));
+        let restored = load(&save(&world));
+        assert_eq!(*restored.rules(), Rules::eight_way(true));
+    }
+
+    #[test]
+    fn victory() {
+        let map = [
+            "victory gold 20",
+            "victory last",
+            "victory reach 1 0",
+            "1  ",
+        ]
+        .join("\n");
+        let world = load(&map);
+        assert_eq!(
+            world.victory(),
+            &[
+                Victory::Gold(20),
+                Victory::LastStanding,
+                Victory::Reach(Coordinate::new(1.0, 0.0))
+            ]
+        );
+
+        let restored = load(&save(&world));
+        assert_eq!(restored.victory(), world.victory());
+        assert!(load("1  ").victory().is_empty());
+    }
+
+    #[test]
+    fn push_limit() {
+        let world = load(&["push 3", "1o "].join("\n"));
+        assert_eq!(world.rules().push_limit, 3);
+        assert!(world
+            .on_coord(Coordinate::new(1.0, 0.0))
+            .unwrap()
+            .is_pushable());
+
+        let restored = load(&save(&world));
+        assert_eq!(*restored.rules(), *world.rules());
+    }
 }
@@ -0,0 +1,146 @@
+use game::puzzle::{Goal, Level};
+use models::coordinate::Coordinate;
+
+/// Line separating the levels of a campaign.
+const SEPARATOR: &str = "---";
+
+const NAME_HEADER: &str = "level ";
+
+/// `goal reach <x> <y>`, `goal deliver <x> <y> [<x> <y> ...]` or
+/// `goal gold`. Defaults to collecting all the gold.
+const GOAL_HEADER: &str = "goal ";
+
+const PAR_HEADER: &str = "par ";
+
+/// Reads a level: its name, goal and par headers, in any order, followed by
+/// a map as read by `basic::load`.
+pub fn load(raw: &str) -> Level {
+    let mut lines: Vec<&str> = raw.split('\n').collect();
+    let mut name = None;
+    let mut goal = Goal::CollectAll;
+    let mut par = None;
+
+    while let Some(raw) = lines.first().cloned() {
+        if let Some(level) = raw.strip_prefix(NAME_HEADER) {
+            name = Some(level.trim().to_string());
+        } else if raw.starts_with(GOAL_HEADER) {
+            goal = load_goal(raw);
+        } else if let Some(moves) = raw.strip_prefix(PAR_HEADER) {
+            let moves = moves.trim().parse();
+            par = Some(moves.unwrap_or_else(|_| panic!("Invalid par header `{}`", raw)));
+        } else {
+            break;
+        }
+        lines.remove(0);
+    }
+
+    Level {
+        name: name.expect("Level without a name"),
+        goal,
+        par: par.expect("Level without a par"),
+        map: lines.join("\n"),
+    }
+}
+
+/// Reads the levels of a campaign, separated by `---` lines, in the order
+/// they're played.
+pub fn load_campaign(raw: &str) -> Vec<Level> {
+    raw.trim_end_matches('\n')
+        .split(&format!("\n{}\n", SEPARATOR))
+        .map(load)
+        .collect()
+}
+
+fn load_goal(raw: &str) -> Goal {
+    let words: Vec<&str> = raw[GOAL_HEADER.len()..].split_whitespace().collect();
+    let numbers: Vec<f32> = words
+        .iter()
+        .skip(1)
+        .map(|n| {
+            n.parse()
+                .unwrap_or_else(|_| panic!("Invalid goal header `{}`", raw))
+        })
+        .collect();
+    let coords: Vec<Coordinate> = numbers
+        .chunks(2)
+        .map(|pair| match pair {
+            [x, y] => Coordinate::new(*x, *y),
+            _ => panic!("Invalid goal header `{}`", raw),
+        })
+        .collect();
+
+    match (words.first(), coords.as_slice()) {
+        (Some(&"reach"), [coord]) => Goal::Reach(*coord),
+        (Some(&"deliver"), targets) if !targets.is_empty() => Goal::Deliver(coords),
+        (Some(&"gold"), []) => Goal::CollectAll,
+        _ => panic!("Invalid goal header `{}`", raw),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_level() {
+        let level = load(&["level First steps", "goal reach 2 0", "par 2", "1  "].join("\n"));
+
+        assert_eq!(level.name, "First steps");
+        assert_eq!(level.goal, Goal::Reach(Coordinate::new(2.0, 0.0)));
+        assert_eq!(level.par, 2);
+        assert_eq!(level.map, "1  ");
+    }
+
+    #[test]
+    fn goals() {
+        let goal = |raw: &str| load(&[raw, "level Test", "par 1", "1"].join("\n")).goal;
+
+        assert_eq!(
+            goal("goal deliver 1 2 3 4"),
+            Goal::Deliver(vec![Coordinate::new(1.0, 2.0), Coordinate::new(3.0, 4.0)])
+        );
+        assert_eq!(goal("goal gold"), Goal::CollectAll);
+        assert_eq!(load("level Test\npar 1\n1").goal, Goal::CollectAll);
+    }
+
+    #[test]
+    #[should_panic]
+    fn odd_goal() {
+        load(&["level Test", "par 1", "goal deliver 1 2 3", "1"].join("\n"));
+    }
+
+    #[test]
+    fn keeps_map_headers() {
+        let level = load(&["level Test", "par 1", "push 2", "1o"].join("\n"));
+        assert_eq!(level.map, "push 2\n1o");
+    }
+
+    #[test]
+    fn headers_only() {
+        let level = load(&["level Test", "par 1"].join("\n"));
+        assert_eq!(level.par, 1);
+        assert_eq!(level.map, "");
+    }
+
+    #[test]
+    fn campaign() {
+        let raw = [
+            "level One",
+            "par 1",
+            "1$",
+            "---",
+            "level Two",
+            "par 3",
+            "1  $",
+            "",
+        ]
+        .join("\n");
+        let levels = load_campaign(&raw);
+
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].name, "One");
+        assert_eq!(levels[0].map, "1$");
+        assert_eq!(levels[1].par, 3);
+        assert_eq!(levels[1].map, "1  $");
+    }
+}
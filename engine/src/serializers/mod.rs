@@ -1 +1,4 @@
 pub mod basic;
+pub mod level;
+pub mod prefabs;
+pub mod rules;
@@ -0,0 +1,283 @@
+use std::fmt;
+use std::str::FromStr;
+
+use controllers;
+use models::components::{Health, Terrain};
+use models::entity::EntityType;
+use models::owner::Owner;
+use models::prefabs::{Color, Prefab, Prefabs};
+
+/// An enum containing all kinds of actors file errors.
+#[derive(Debug, PartialEq)]
+pub enum PrefabsError {
+    Malformed(String),
+    UnknownField(String),
+    InvalidValue(String, String),
+    MissingField(String, &'static str),
+    DuplicateGlyph(char),
+}
+
+impl fmt::Display for PrefabsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PrefabsError::Malformed(line) => {
+                write!(f, "Expected `[<name>]` or `<field> = <value>`: {}", line)
+            }
+            PrefabsError::UnknownField(field) => write!(f, "Unknown field: {}", field),
+            PrefabsError::InvalidValue(field, value) => {
+                write!(f, "Invalid value for {}: {}", field, value)
+            }
+            PrefabsError::MissingField(name, field) => {
+                write!(f, "Actor {} is missing its {}", name, field)
+            }
+            PrefabsError::DuplicateGlyph(glyph) => {
+                write!(f, "Several actors are drawn as `{}`", glyph)
+            }
+        }
+    }
+}
+
+pub type PrefabsResult<T> = std::result::Result<T, PrefabsError>;
+
+/// Fields of an actor, as read before they're checked.
+#[derive(Default)]
+struct Section<'a> {
+    name: &'a str,
+    glyph: Option<char>,
+    kind: Option<&'a str>,
+    prefab: Option<Prefab>,
+}
+
+/// Reads an actors file: a `[<name>]` line for each actor, followed by its
+/// `<field> = <value>` lines, `glyph` and `kind` being required. Blank lines
+/// and lines starting with `#` are skipped.
+pub fn load(raw: &str) -> PrefabsResult<Prefabs> {
+    let mut prefabs = Prefabs::new();
+    let mut section: Option<Section> = None;
+
+    for line in raw.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            if let Some(section) = section.take() {
+                register(&mut prefabs, section)?;
+            }
+            section = Some(Section {
+                name: line[1..line.len() - 1].trim(),
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let mut parts = line.splitn(2, '=').map(str::trim);
+        match (section.as_mut(), parts.next(), parts.next()) {
+            (Some(section), Some(field), Some(value)) if !field.is_empty() => {
+                set(section, field, value)?
+            }
+            _ => return Err(PrefabsError::Malformed(line.to_string())),
+        }
+    }
+
+    if let Some(section) = section {
+        register(&mut prefabs, section)?;
+    }
+    Ok(prefabs)
+}
+
+fn register(prefabs: &mut Prefabs, section: Section) -> PrefabsResult<()> {
+    let missing = |field| PrefabsError::MissingField(section.name.to_string(), field);
+    let kind = section.kind.ok_or_else(|| missing("kind"))?;
+    let glyph = match (kind, section.glyph) {
+        ("player", _) => '1',
+        (_, Some(glyph)) => glyph,
+        (_, None) => return Err(missing("glyph")),
+    };
+
+    let entity_type = match kind {
+        "player" => EntityType::Player(1),
+        "enemy" => EntityType::Enemy(glyph),
+        "obstacle" => EntityType::Obstacle(glyph),
+        "hole" => EntityType::Hole(glyph),
+        "treasure" => EntityType::Treasure(glyph),
+        "wagon" => EntityType::Wagon(glyph),
+        "pushable" => EntityType::Pushable(glyph),
+        _ => return Err(invalid("kind", kind)),
+    };
+    let taken = prefabs
+        .by_glyph(glyph)
+        .is_some_and(|other| other.name != section.name);
+    if kind != "player" && taken {
+        return Err(PrefabsError::DuplicateGlyph(glyph));
+    }
+
+    let name = section.name;
+    let prefab = section
+        .prefab
+        .unwrap_or_else(|| Prefab::new(name, entity_type));
+    prefabs.register(Prefab {
+        name: name.to_string(),
+        entity_type,
+        ..prefab
+    });
+    Ok(())
+}
+
+fn set<'a>(section: &mut Section<'a>, field: &str, value: &'a str) -> PrefabsResult<()> {
+    let name = section.name;
+    let prefab = section
+        .prefab
+        .get_or_insert_with(|| Prefab::new(name, EntityType::Enemy('?')));
+
+    match field {
+        "glyph" => {
+            let mut chars = value.chars();
+            section.glyph = match (chars.next(), chars.next()) {
+                (Some(glyph), None) if !glyph.is_ascii_digit() && glyph != ' ' => Some(glyph),
+                _ => return Err(invalid(field, value)),
+            }
+        }
+        "kind" => section.kind = Some(value),
+        "owner" => {
+            prefab.owner = match value {
+                "engine" => Owner::Engine,
+                "outlaws" => Owner::Outlaws,
+                _ => return Err(invalid(field, value)),
+            }
+        }
+        "gold" => prefab.inventory.gold = parse(field, value)?,
+        "ammo" => prefab.inventory.ammo = parse(field, value)?,
+        "health" => prefab.health = Some(Health::new(parse(field, value)?)),
+        "size" => {
+            prefab.size = parse(field, value)?;
+            if prefab.size < 1 {
+                return Err(invalid(field, value));
+            }
+        }
+        "terrain" => {
+            let blocks_sight = match value {
+                "open" => false,
+                "opaque" => true,
+                _ => return Err(invalid(field, value)),
+            };
+            let terrain = prefab.terrain.get_or_insert(Terrain::default());
+            terrain.blocks_sight = blocks_sight;
+        }
+        "passable" => {
+            let terrain = prefab.terrain.get_or_insert(Terrain::default());
+            terrain.passable = parse(field, value)?;
+        }
+        "color" => {
+            let channels = value
+                .split_whitespace()
+                .map(|channel| parse(field, channel))
+                .collect::<PrefabsResult<Vec<f32>>>()?;
+            prefab.color = match channels.as_slice() {
+                [r, g, b] if channels.iter().all(|c| (0.0..=1.0).contains(c)) => Some(Color {
+                    r: *r,
+                    g: *g,
+                    b: *b,
+                }),
+                _ => return Err(invalid(field, value)),
+            }
+        }
+        "ai" => {
+            if controllers::by_name(value, 0).is_none() {
+                return Err(invalid(field, value));
+            }
+            prefab.ai = Some(value.to_string());
+        }
+        _ => return Err(PrefabsError::UnknownField(field.to_string())),
+    }
+
+    Ok(())
+}
+
+fn invalid(field: &str, value: &str) -> PrefabsError {
+    PrefabsError::InvalidValue(field.to_string(), value.to_string())
+}
+
+fn parse<T: FromStr>(field: &str, value: &str) -> PrefabsResult<T> {
+    value.parse().map_err(|_| invalid(field, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::coordinate::Coordinate;
+
+    #[test]
+    fn load_actors() {
+        let raw = [
+            "# Lawmen",
+            "[sheriff]",
+            "glyph = S",
+            "kind = enemy",
+            "  ammo=6  ",
+            "health = 3",
+            "color = 0 0 1",
+            "ai = greedy",
+            "",
+            "[brush]",
+            "glyph = %",
+            "kind = obstacle",
+            "passable = true",
+        ]
+        .join("\n");
+        let prefabs = load(&raw).unwrap();
+
+        let sheriff = prefabs.get("sheriff").unwrap();
+        assert_eq!(sheriff.entity_type, EntityType::Enemy('S'));
+        assert_eq!(sheriff.owner, Owner::Engine);
+        assert_eq!(sheriff.inventory.ammo, 6);
+        assert_eq!(sheriff.health, Some(Health::new(3)));
+        assert_eq!(sheriff.color.unwrap().b, 1.0);
+        assert_eq!(sheriff.ai, Some("greedy".to_string()));
+
+        let brush = prefabs.spawn_glyph('%', Coordinate::new(0.0, 0.0)).unwrap();
+        assert!(brush.terrain.unwrap().passable);
+        assert!(!brush.terrain.unwrap().blocks_sight);
+        assert!(prefabs.player().is_none());
+    }
+
+    #[test]
+    fn builtin_actors_load() {
+        let raw = include_str!("../../actors.txt");
+        assert_eq!(load(raw).as_ref(), Ok(Prefabs::builtin()));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            load("glyph = S"),
+            Err(PrefabsError::Malformed("glyph = S".to_string()))
+        );
+        assert_eq!(
+            load("[sheriff]\nkind = enemy\nbadge = gold"),
+            Err(PrefabsError::UnknownField("badge".to_string()))
+        );
+        assert_eq!(
+            load("[sheriff]\nkind = lawman\nglyph = S"),
+            Err(PrefabsError::InvalidValue(
+                "kind".to_string(),
+                "lawman".to_string()
+            ))
+        );
+        assert_eq!(
+            load("[sheriff]\nkind = enemy\nai = clever"),
+            Err(PrefabsError::InvalidValue(
+                "ai".to_string(),
+                "clever".to_string()
+            ))
+        );
+        assert_eq!(
+            load("[sheriff]\nkind = enemy"),
+            Err(PrefabsError::MissingField("sheriff".to_string(), "glyph"))
+        );
+        assert_eq!(
+            load("[rock]\nglyph = #\nkind = obstacle\n[hill]\nglyph = #\nkind = obstacle"),
+            Err(PrefabsError::DuplicateGlyph('#'))
+        );
+    }
+}
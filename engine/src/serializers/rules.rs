@@ -0,0 +1,229 @@
+use std::fmt;
+use std::str::FromStr;
+
+use models::coordinate::Coordinate;
+use models::rules::{Connectivity, Rules};
+
+/// An enum containing all kinds of rules file errors.
+#[derive(Debug, PartialEq)]
+pub enum RulesError {
+    Malformed(String),
+    UnknownRule(String),
+    InvalidValue(String, String),
+    OutOfRange(&'static str),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::Malformed(line) => write!(f, "Expected `<rule> = <value>`: {}", line),
+            RulesError::UnknownRule(rule) => write!(f, "Unknown rule: {}", rule),
+            RulesError::InvalidValue(rule, value) => {
+                write!(f, "Invalid value for {}: {}", rule, value)
+            }
+            RulesError::OutOfRange(rule) => write!(f, "Rule is out of range: {}", rule),
+        }
+    }
+}
+
+pub type RulesResult<T> = std::result::Result<T, RulesError>;
+
+/// Reads a rules file: one `<rule> = <value>` line per rule, the rules left
+/// out keeping their defaults. Blank lines and lines starting with `#` are
+/// skipped.
+pub fn load(raw: &str) -> RulesResult<Rules> {
+    let mut rules = Rules::default();
+
+    for line in raw.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.splitn(2, '=').map(str::trim);
+        match (parts.next(), parts.next()) {
+            (Some(rule), Some(value)) if !rule.is_empty() => set(&mut rules, rule, value)?,
+            _ => return Err(RulesError::Malformed(line.to_string())),
+        }
+    }
+
+    validate(&rules)?;
+    Ok(rules)
+}
+
+/// Writes every rule, as read by `load`.
+pub fn print(rules: &Rules) -> String {
+    let topology = match rules.connectivity {
+        Connectivity::Four => "four",
+        Connectivity::Eight => "eight",
+        Connectivity::Hex => "hex",
+    };
+
+    [
+        format!("topology = {}", topology),
+        format!("corner_cutting = {}", rules.corner_cutting),
+        format!("push_limit = {}", rules.push_limit),
+        format!("sight_radius = {}", rules.sight_radius),
+        format!("shot_range = {}", rules.shot_range),
+        format!("hit_chance = {}", rules.hit_chance),
+        format!("cover_bonus = {}", rules.cover_bonus),
+        format!("damage = {}", rules.damage),
+        format!("board = {} {}", rules.board.x, rules.board.y),
+    ]
+    .iter()
+    .map(|line| format!("{}\n", line))
+    .collect()
+}
+
+/// Checks the rules make for a playable game.
+pub fn validate(rules: &Rules) -> RulesResult<()> {
+    if rules.sight_radius < 0 {
+        return Err(RulesError::OutOfRange("sight_radius"));
+    }
+    if rules.shot_range < 0 {
+        return Err(RulesError::OutOfRange("shot_range"));
+    }
+    if !(0.0..=1.0).contains(&rules.hit_chance) {
+        return Err(RulesError::OutOfRange("hit_chance"));
+    }
+    if !(0.0..=rules.hit_chance).contains(&rules.cover_bonus) {
+        return Err(RulesError::OutOfRange("cover_bonus"));
+    }
+    if rules.damage < 0 {
+        return Err(RulesError::OutOfRange("damage"));
+    }
+    if rules.board.x < 1.0 || rules.board.y < 1.0 {
+        return Err(RulesError::OutOfRange("board"));
+    }
+
+    Ok(())
+}
+
+fn set(rules: &mut Rules, rule: &str, value: &str) -> RulesResult<()> {
+    let invalid = || RulesError::InvalidValue(rule.to_string(), value.to_string());
+
+    match rule {
+        "topology" => {
+            rules.connectivity = match value {
+                "four" => Connectivity::Four,
+                "eight" => Connectivity::Eight,
+                "hex" => Connectivity::Hex,
+                _ => return Err(invalid()),
+            }
+        }
+        "corner_cutting" => rules.corner_cutting = parse(rule, value)?,
+        "push_limit" => rules.push_limit = parse(rule, value)?,
+        "sight_radius" => rules.sight_radius = parse(rule, value)?,
+        "shot_range" => rules.shot_range = parse(rule, value)?,
+        "hit_chance" => rules.hit_chance = parse(rule, value)?,
+        "cover_bonus" => rules.cover_bonus = parse(rule, value)?,
+        "damage" => rules.damage = parse(rule, value)?,
+        "board" => {
+            let sizes = value
+                .split_whitespace()
+                .map(|size| parse(rule, size))
+                .collect::<RulesResult<Vec<f32>>>()?;
+            rules.board = match sizes.as_slice() {
+                [x, y] => Coordinate::new(*x, *y),
+                _ => return Err(invalid()),
+            }
+        }
+        _ => return Err(RulesError::UnknownRule(rule.to_string())),
+    }
+
+    Ok(())
+}
+
+fn parse<T: FromStr>(rule: &str, value: &str) -> RulesResult<T> {
+    value
+        .parse()
+        .map_err(|_| RulesError::InvalidValue(rule.to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        assert_eq!(load(""), Ok(Rules::default()));
+        assert_eq!(load("# Nothing to change\n\n"), Ok(Rules::default()));
+    }
+
+    #[test]
+    fn load_rules() {
+        let raw = [
+            "topology = eight",
+            "corner_cutting = true",
+            "  sight_radius=5  ",
+            "hit_chance = 0.5",
+            "damage = 2",
+            "board = 12 6",
+        ]
+        .join("\n");
+        let rules = load(&raw).unwrap();
+
+        assert_eq!(rules.connectivity, Connectivity::Eight);
+        assert!(rules.corner_cutting);
+        assert_eq!(rules.sight_radius, 5);
+        assert_eq!(rules.hit_chance, 0.5);
+        assert_eq!(rules.damage, 2);
+        assert_eq!(rules.board, Coordinate::new(12.0, 6.0));
+        assert_eq!(rules.shot_range, Rules::default().shot_range);
+    }
+
+    #[test]
+    fn load_what_is_printed() {
+        let rules = Rules {
+            push_limit: 3,
+            cover_bonus: 0.1,
+            damage: 3,
+            ..Rules::hex()
+        };
+        assert_eq!(load(&print(&rules)), Ok(rules));
+        assert_eq!(load(&print(&Rules::default())), Ok(Rules::default()));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            load("sight_radius 5"),
+            Err(RulesError::Malformed("sight_radius 5".to_string()))
+        );
+        assert_eq!(
+            load("gravity = 9.8"),
+            Err(RulesError::UnknownRule("gravity".to_string()))
+        );
+        assert_eq!(
+            load("topology = triangles"),
+            Err(RulesError::InvalidValue(
+                "topology".to_string(),
+                "triangles".to_string()
+            ))
+        );
+        assert_eq!(
+            load("board = 8"),
+            Err(RulesError::InvalidValue(
+                "board".to_string(),
+                "8".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(
+            load("hit_chance = 1.5"),
+            Err(RulesError::OutOfRange("hit_chance"))
+        );
+        assert_eq!(
+            load("hit_chance = 0.2\ncover_bonus = 0.3"),
+            Err(RulesError::OutOfRange("cover_bonus"))
+        );
+        assert_eq!(
+            load("shot_range = -1"),
+            Err(RulesError::OutOfRange("shot_range"))
+        );
+        assert_eq!(load("damage = -1"), Err(RulesError::OutOfRange("damage")));
+        assert_eq!(load("board = 0 4"), Err(RulesError::OutOfRange("board")));
+    }
+}
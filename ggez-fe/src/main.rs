@@ -6,103 +6,612 @@ use ggez::graphics;
 use ggez::graphics::{DrawMode, Point2};
 use ggez::{Context, GameResult};
 use std::env;
+use std::fs;
 use std::path;
 
-use engine::models::{Entity, EntityType};
+use engine::actions::{movement, ActionData};
+use engine::controllers::keyboard::closest_shot;
+use engine::controllers::{self, Controller, Keyboard, KeyboardInput, Scripted};
+use engine::game::{self, BestScores, GameMode, GameState, Level, Puzzle, Session, Victory};
+use engine::models::actors::player;
+use engine::models::direction::{self, Direction};
+use engine::models::rules::Connectivity;
+use engine::models::{Coordinate, Entity, EntityType, Owner, Prefabs, Rules, World, WorldView};
+use engine::net::protocol::ServerMessage;
+use engine::net::Client;
+use engine::serializers::{basic, level, prefabs, rules};
+
+const DEFAULT_MAP: [&str; 10] = [
+    "               ",
+    " 1           2 ",
+    "               ",
+    "      ~~~~##   ",
+    "   $   ~~~~~#  ",
+    "               ",
+    "   #        $  ",
+    "  ###          ",
+    "   #     B     ",
+    "               ",
+];
 
 // First we make a structure to contain the game's state
 struct MainState {
     text: graphics::Text,
-    world: engine::models::World,
-    first_player_id: i32,
-    second_player_id: i32,
+    small_font: graphics::Font,
+    session: Session,
+    prefabs: Prefabs,
+    inputs: Vec<(KeyScheme, KeyboardInput)>,
+    has_keyboard: bool,
+    keyboard_players: Vec<i32>,
+    frames: u32,
+    client: Option<Client>,
+    /// Bot playing the connected player, if it isn't played by keys.
+    remote_bot: Option<Box<dyn Controller>>,
+    /// How the game ended, as announced by the server.
+    game_over: Option<GameState>,
+    campaign: Option<Campaign>,
+}
+
+/// Puzzle levels played one after another, best scores being kept on disk.
+struct Campaign {
+    levels: Vec<Level>,
+    current: usize,
+    puzzle: Puzzle,
+    scores: BestScores,
+}
+
+impl Campaign {
+    fn status(&self) -> String {
+        let level = self.puzzle.level();
+        let mut status = format!(
+            "{} - moves: {} (par {})",
+            level.name,
+            self.puzzle.moves(),
+            level.par
+        );
+        if let Some(best) = self.scores.best(&level.name) {
+            status.push_str(&format!(", best: {}", best));
+        }
+
+        if !self.puzzle.is_solved() {
+            status
+        } else if self.current + 1 < self.levels.len() {
+            status + " - solved! N for the next level"
+        } else {
+            status + " - campaign complete!"
+        }
+    }
+}
+
+/// Where best puzzle scores are kept: the home directory, or the current
+/// one without it.
+fn scores_path() -> path::PathBuf {
+    let dir = env::var("HOME")
+        .map(path::PathBuf::from)
+        .unwrap_or_default();
+    dir.join(".wwtrader-scores")
+}
+
+// Frames between turns when only bots are playing
+const BOT_TURN_FRAMES: u32 = 20;
+
+/// Keys a player can be moved with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum KeyScheme {
+    /// Arrows, plus the numpad for diagonals, shooting with Return.
+    Arrows,
+    /// WASD, plus QEZC for diagonals, shooting with Space.
+    Wasd,
+}
+
+impl KeyScheme {
+    const ALL: [KeyScheme; 2] = [KeyScheme::Arrows, KeyScheme::Wasd];
+
+    fn by_name(name: &str) -> Option<KeyScheme> {
+        match name {
+            "arrows" => Some(KeyScheme::Arrows),
+            "wasd" => Some(KeyScheme::Wasd),
+            _ => None,
+        }
+    }
+
+    fn direction(self, keycode: Keycode) -> Option<Direction> {
+        match self {
+            KeyScheme::Arrows => arrow_key(keycode),
+            KeyScheme::Wasd => wasd_key(keycode),
+        }
+    }
+
+    fn shoots(self, keycode: Keycode) -> bool {
+        match self {
+            KeyScheme::Arrows => keycode == Keycode::Return,
+            KeyScheme::Wasd => keycode == Keycode::Space,
+        }
+    }
+}
+
+/// Who plays a player, going by its `--playerN` option: a key scheme,
+/// `keyboard` for the first scheme no one took yet, or a bot. Players left
+/// without keys wait.
+fn controller_for(
+    name: &str,
+    inputs: &[(KeyScheme, KeyboardInput)],
+    taken: &mut Vec<KeyScheme>,
+) -> (Box<dyn Controller>, Option<KeyScheme>) {
+    let scheme = match name {
+        "keyboard" => inputs
+            .iter()
+            .map(|(scheme, _)| *scheme)
+            .find(|scheme| !taken.contains(scheme)),
+        _ => KeyScheme::by_name(name),
+    };
+    if let Some((scheme, input)) = inputs.iter().find(|(other, _)| Some(*other) == scheme) {
+        taken.push(*scheme);
+        return (Box::new(Keyboard::new(input.clone())), Some(*scheme));
+    }
+
+    let bot = controllers::by_name(name, 0).unwrap_or_else(|| {
+        eprintln!("No keys or bot `{}` left, the player waits", name);
+        Box::new(Scripted::new(vec![]))
+    });
+    (bot, None)
 }
 
 impl MainState {
-    fn new(ctx: &mut Context) -> MainState {
+    fn new(
+        ctx: &mut Context,
+        client: Option<Client>,
+        players: &[String],
+        mode: Box<dyn GameMode>,
+        mut world: World,
+        prefabs: Prefabs,
+        levels: Option<Vec<Level>>,
+    ) -> MainState {
         // The ttf file will be in your resources directory. Later, we
         // will mount that directory so we can omit it in the path here.
         let font = graphics::Font::new(ctx, "/DejaVuSerif.ttf", 48).unwrap();
         let text = graphics::Text::new(ctx, "Wild Wild Trader", &font).unwrap();
+        let small_font = graphics::Font::new(ctx, "/DejaVuSerif.ttf", 18).unwrap();
+
+        let inputs: Vec<(KeyScheme, KeyboardInput)> = KeyScheme::ALL
+            .iter()
+            .map(|scheme| (*scheme, KeyboardInput::new()))
+            .collect();
+
+        // Maps declaring how they're won keep their own conditions
+        if world.victory().is_empty() {
+            let mut victory = vec![Victory::Gold(20), Victory::Richest(500)];
+            // A lone player would be the last one standing from the start
+            if world.query().players().count() > 1 {
+                victory.insert(0, Victory::LastStanding);
+            }
+            world.set_victory(victory);
+        }
+        let mut session = Session::new(world);
+        session.set_mode(mode);
+
+        let seats: Vec<(i32, i8)> = session
+            .world
+            .query()
+            .players()
+            .collect()
+            .into_iter()
+            .filter_map(|entity| match entity.entity_type {
+                EntityType::Player(n) => Some((entity.id, n)),
+                _ => None,
+            })
+            .collect();
+        let mut taken = vec![];
+        let mut keyboard_players = vec![];
+        for (entity_id, n) in seats {
+            let (controller, scheme) =
+                controller_for(&players[n as usize - 1], &inputs, &mut taken);
+            if scheme.is_some() {
+                keyboard_players.push(entity_id);
+            }
+            session.seat(entity_id, controller);
+        }
+        session.seat_actors(&prefabs, 0);
 
-        let world_data = [
-            "               ",
-            " 1           2 ",
-            "               ",
-            "      ~~~~##   ",
-            "       ~~~~~#  ",
-            "               ",
-            "   #           ",
-            "  ###          ",
-            "   #     B     ",
-            "               ",
-        ]
-        .join("\n");
-
-        MainState {
+        // Connected players are played on the server, possibly by a bot
+        let remote_bot = client.as_ref().and_then(|client| {
+            let name = &players[client.player as usize - 1];
+            match KeyScheme::by_name(name) {
+                Some(_) => None,
+                None => controllers::by_name(name, 0),
+            }
+        });
+
+        let mut state = MainState {
             text,
-            world: engine::serializers::basic::load(&world_data),
-            first_player_id: 1,
-            second_player_id: 2,
+            small_font,
+            session,
+            prefabs,
+            inputs,
+            has_keyboard: !keyboard_players.is_empty(),
+            keyboard_players,
+            frames: 0,
+            client,
+            remote_bot,
+            game_over: None,
+            campaign: None,
+        };
+
+        if let Some(levels) = levels {
+            let scores = BestScores::load(&scores_path()).unwrap_or_else(|error| {
+                eprintln!("Can't read best scores: {}", error);
+                BestScores::new()
+            });
+            let (puzzle, _) = Puzzle::start(levels[0].clone());
+            state.campaign = Some(Campaign {
+                levels,
+                current: 0,
+                puzzle,
+                scores,
+            });
+            state.start_level(0);
+        }
+
+        state
+    }
+
+    /// Replaces the game with a fresh start of the campaign's level `index`,
+    /// played by the first player's keys.
+    fn start_level(&mut self, index: usize) {
+        let campaign = match self.campaign {
+            Some(ref mut campaign) => campaign,
+            None => return,
+        };
+
+        let (puzzle, world) = Puzzle::start(campaign.levels[index].clone());
+        self.session = Session::new(world);
+        self.keyboard_players = puzzle.player_id().into_iter().collect();
+        for entity_id in &self.keyboard_players {
+            let keyboard = Keyboard::new(self.inputs[0].1.clone());
+            self.session.seat(*entity_id, Box::new(keyboard));
+        }
+        self.has_keyboard = true;
+
+        campaign.current = index;
+        campaign.puzzle = puzzle;
+    }
+
+    /// Counts the turn's move, saving the score once the level is solved.
+    fn track_puzzle(&mut self) {
+        let campaign = match self.campaign {
+            Some(ref mut campaign) => campaign,
+            None => return,
+        };
+
+        let was_solved = campaign.puzzle.is_solved();
+        campaign.puzzle.update(&self.session.world);
+        if was_solved || !campaign.puzzle.is_solved() {
+            return;
+        }
+
+        let name = campaign.puzzle.level().name.clone();
+        if campaign.scores.record(&name, campaign.puzzle.moves()) {
+            if let Err(error) = campaign.scores.save(&scores_path()) {
+                eprintln!("Can't save best scores: {}", error);
+            }
+        }
+    }
+
+    fn update_from_server(&mut self) {
+        let client = match self.client {
+            Some(ref client) => client,
+            None => return,
+        };
+
+        let mut new_state = false;
+        loop {
+            match client.try_receive() {
+                Ok(Some(ServerMessage::State { mut world, .. })) => {
+                    // The server only sends what we see, remember what we explored
+                    if let Some(memory) = self.session.world.visibility(client.player) {
+                        world.restore_visibility(client.player, memory.clone());
+                    }
+                    world.update_visibility();
+                    self.session.world = *world;
+                    new_state = true;
+                }
+                Ok(Some(ServerMessage::Rejected(reason))) => {
+                    eprintln!("Action rejected: {}", reason)
+                }
+                Ok(Some(ServerMessage::Error(reason))) => eprintln!("Server error: {}", reason),
+                Ok(Some(ServerMessage::GameOver(state))) => self.game_over = Some(state),
+                Ok(Some(_)) => (),
+                Ok(None) => break,
+                Err(error) => {
+                    eprintln!("Lost connection: {}", error);
+                    self.client = None;
+                    return;
+                }
+            }
+        }
+
+        // The server waits for our action on every new turn
+        if let (true, Some(mut bot)) = (new_state, self.remote_bot.take()) {
+            self.submit(|view, entity_id| bot.decide(view, entity_id));
+            self.remote_bot = Some(bot);
+        }
+    }
+
+    fn own_player_id(&self, player: i8) -> Option<i32> {
+        self.session
+            .world
+            .find_player(player)
+            .map(|entity| entity.id)
+    }
+
+    fn submit<F>(&mut self, decide: F)
+    where
+        F: FnOnce(&WorldView, i32) -> Option<ActionData>,
+    {
+        let player = match self.client {
+            Some(ref client) => client.player,
+            None => return,
+        };
+
+        let action = self
+            .own_player_id(player)
+            .and_then(|entity_id| decide(&self.view(), entity_id));
+
+        if let (Some(action), Some(ref mut client)) = (action, &mut self.client) {
+            if let Err(error) = client.submit(action) {
+                eprintln!("Failed to submit action: {}", error);
+            }
         }
     }
 }
 
+/// Colours of players 1 to 8.
+const PLAYER_COLORS: [graphics::Color; 8] = [
+    graphics::Color {
+        r: 1.0,
+        g: 1.0,
+        b: 1.0,
+        a: 1.0,
+    },
+    graphics::Color {
+        r: 0.0,
+        g: 1.0,
+        b: 0.0,
+        a: 1.0,
+    },
+    graphics::Color {
+        r: 0.0,
+        g: 0.8,
+        b: 1.0,
+        a: 1.0,
+    },
+    graphics::Color {
+        r: 1.0,
+        g: 0.0,
+        b: 1.0,
+        a: 1.0,
+    },
+    graphics::Color {
+        r: 1.0,
+        g: 0.5,
+        b: 0.0,
+        a: 1.0,
+    },
+    graphics::Color {
+        r: 0.6,
+        g: 0.4,
+        b: 1.0,
+        a: 1.0,
+    },
+    graphics::Color {
+        r: 0.6,
+        g: 1.0,
+        b: 0.6,
+        a: 1.0,
+    },
+    graphics::Color {
+        r: 1.0,
+        g: 0.6,
+        b: 0.7,
+        a: 1.0,
+    },
+];
+
 const START_X: f32 = 10.0;
 const START_Y: f32 = 90.0;
 const ENTITY_SIZE: f32 = 50.0;
 
 impl MainState {
+    fn is_hex(&self) -> bool {
+        self.session.world.rules().connectivity == Connectivity::Hex
+    }
+
+    /// Screen position of the middle of a tile. Hexes are pointy topped and
+    /// as wide as a square tile, each row shifting half a hex to the right.
+    fn tile_center(&self, coord: Coordinate) -> Point2 {
+        if self.is_hex() {
+            Point2::new(
+                START_X + (coord.x + coord.y / 2.0) * ENTITY_SIZE + ENTITY_SIZE / 2.0,
+                START_Y + coord.y * ENTITY_SIZE * 3f32.sqrt() / 2.0 + ENTITY_SIZE / 3f32.sqrt(),
+            )
+        } else {
+            Point2::new(
+                START_X + coord.x * ENTITY_SIZE + ENTITY_SIZE / 2.0,
+                START_Y + coord.y * ENTITY_SIZE + ENTITY_SIZE / 2.0,
+            )
+        }
+    }
+
+    /// Outline of a tile, clockwise.
+    fn tile_outline(&self, coord: Coordinate) -> Vec<Point2> {
+        let center = self.tile_center(coord);
+        if self.is_hex() {
+            let radius = ENTITY_SIZE / 3f32.sqrt();
+            (0..6)
+                .map(|corner| {
+                    let angle = (60.0 * corner as f32 - 90.0).to_radians();
+                    Point2::new(
+                        center.x + radius * angle.cos(),
+                        center.y + radius * angle.sin(),
+                    )
+                })
+                .collect()
+        } else {
+            let half = ENTITY_SIZE / 2.0;
+            vec![
+                Point2::new(center.x - half, center.y - half),
+                Point2::new(center.x + half, center.y - half),
+                Point2::new(center.x + half, center.y + half),
+                Point2::new(center.x - half, center.y + half),
+            ]
+        }
+    }
+
     fn draw_entity(&self, ctx: &mut Context, entity: &Entity) -> GameResult<()> {
         let color = match entity.entity_type {
-            EntityType::Player(1) => graphics::Color {
-                r: 1.0,
-                g: 1.0,
-                b: 1.0,
-                a: 1.0,
+            EntityType::Player(n) => PLAYER_COLORS[(n as usize - 1) % PLAYER_COLORS.len()],
+            _ => match self.prefabs.of(entity).and_then(|prefab| prefab.color) {
+                Some(color) => graphics::Color {
+                    r: color.r,
+                    g: color.g,
+                    b: color.b,
+                    a: 1.0,
+                },
+                None => graphics::WHITE,
             },
-            EntityType::Player(2) => graphics::Color {
-                r: 0.0,
-                g: 1.0,
-                b: 0.0,
-                a: 1.0,
+        };
+
+        // Entities covering several tiles, such as towns, fill each of them
+        let mut builder = graphics::MeshBuilder::new();
+        for cell in entity.cells() {
+            builder.circle(
+                DrawMode::Fill,
+                self.tile_center(cell),
+                ENTITY_SIZE / 2.0,
+                1.0,
+            );
+        }
+        let mesh = builder.build(ctx)?;
+
+        graphics::set_color(ctx, color)?;
+        graphics::draw(ctx, &mesh, graphics::Point2::new(0.0, 0.0), 0.0)?;
+
+        Ok(())
+    }
+
+    /// Whose fog of war is drawn: the connected player, or the only
+    /// keyboard player. Everything is shown when several people share the
+    /// screen.
+    fn perspective(&self) -> Option<i8> {
+        if let Some(ref client) = self.client {
+            return Some(client.player);
+        }
+
+        match self.keyboard_players.as_slice() {
+            [entity_id] => match self.session.world.get_entity(*entity_id)?.owner {
+                Owner::Player(player) => Some(player),
+                _ => None,
             },
-            EntityType::Player(_) => graphics::Color {
+            _ => None,
+        }
+    }
+
+    fn view(&self) -> WorldView<'_> {
+        match self.perspective() {
+            Some(player) => WorldView::for_player(&self.session.world, player),
+            None => WorldView::new(&self.session.world),
+        }
+    }
+
+    /// Players moved from this screen, whose legal moves get highlighted.
+    fn human_players(&self) -> Vec<i32> {
+        match self.client {
+            Some(ref client) => self.own_player_id(client.player).into_iter().collect(),
+            None => self.keyboard_players.clone(),
+        }
+    }
+
+    fn draw_legal_moves(&self, ctx: &mut Context, entity_id: i32) -> GameResult<()> {
+        let view = self.view();
+        let entity = match view.get_entity(entity_id) {
+            Some(entity) => entity,
+            None => return Ok(()),
+        };
+
+        graphics::set_color(
+            ctx,
+            graphics::Color {
                 r: 1.0,
                 g: 1.0,
                 b: 1.0,
-                a: 1.0,
+                a: 0.2,
             },
-            EntityType::Enemy(_) => graphics::Color {
-                r: 1.0,
-                g: 0.0,
-                b: 0.0,
-                a: 1.0,
-            },
-            EntityType::Obstacle(_) => graphics::Color {
-                r: 0.5,
-                g: 0.5,
-                b: 0.5,
-                a: 1.0,
-            },
-            EntityType::Hole(_) => graphics::Color {
-                r: 0.2,
-                g: 0.2,
-                b: 0.8,
-                a: 1.0,
-            },
-        };
+        )?;
 
-        let x = START_X + entity.coord.x * ENTITY_SIZE + ENTITY_SIZE / 2.0;
-        let y = START_Y + entity.coord.y * ENTITY_SIZE + ENTITY_SIZE / 2.0;
+        for action in view.legal_actions(entity_id) {
+            if let Some(dir) = action.direction() {
+                let coord = movement::operate(&self.session.world, entity.coord, dir);
+                let center = self.tile_center(coord);
 
-        let mesh = graphics::MeshBuilder::new()
-            //.rectangle(graphics::DrawMode::Fill, graphics::Point2::new(100.0, 100.0), 100.0, 100.0, graphics::WHITE)
-            .circle(DrawMode::Fill, Point2::new(x, y), ENTITY_SIZE / 2.0, 1.0)
-            .build(ctx)?;
+                let mesh = graphics::MeshBuilder::new()
+                    .circle(DrawMode::Fill, center, ENTITY_SIZE / 6.0, 1.0)
+                    .build(ctx)?;
+                graphics::draw(ctx, &mesh, graphics::Point2::new(0.0, 0.0), 0.0)?;
+            }
+        }
 
-        graphics::set_color(ctx, color)?;
-        graphics::draw(ctx, &mesh, graphics::Point2::new(0.0, 0.0), 0.0)?;
+        Ok(())
+    }
+
+    /// Dims explored tiles out of sight and hides unexplored ones.
+    fn draw_fog(&self, ctx: &mut Context) -> GameResult<()> {
+        let view = self.view();
+        if view.player().is_none() {
+            return Ok(());
+        }
+
+        let right_edge = view.right_edge();
+        for y in 0..=right_edge.y as i32 {
+            for x in 0..=right_edge.x as i32 {
+                let coord = Coordinate::new(x as f32, y as f32);
+                if view.is_visible(coord) {
+                    continue;
+                }
+
+                let a = if view.is_explored(coord) { 0.6 } else { 1.0 };
+                graphics::set_color(
+                    ctx,
+                    graphics::Color {
+                        r: 0.0,
+                        g: 0.0,
+                        b: 0.0,
+                        a,
+                    },
+                )?;
+                let mesh = graphics::MeshBuilder::new()
+                    .polygon(DrawMode::Fill, &self.tile_outline(coord))
+                    .build(ctx)?;
+                graphics::draw(ctx, &mesh, graphics::Point2::new(0.0, 0.0), 0.0)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Outlines every hex, as a rhombus board has no straight border.
+    fn draw_hex_grid(&self, ctx: &mut Context) -> GameResult<()> {
+        let right_edge = self.session.world.right_edge;
+        let mut builder = graphics::MeshBuilder::new();
+        for y in 0..=right_edge.y as i32 {
+            for x in 0..=right_edge.x as i32 {
+                let mut outline = self.tile_outline(Coordinate::new(x as f32, y as f32));
+                outline.push(outline[0]);
+                builder.line(&outline, 1.0);
+            }
+        }
+
+        let grid = builder.build(ctx)?;
+        graphics::draw(ctx, &grid, graphics::Point2::new(0.0, 0.0), 0.0)?;
 
         Ok(())
     }
@@ -115,13 +624,32 @@ impl MainState {
 // that you can override if you wish, but the defaults are fine.
 impl event::EventHandler for MainState {
     fn update(&mut self, _ctx: &mut Context) -> GameResult<()> {
-        match engine::game::runner::run(&mut self.world) {
-            Ok(_) => Ok(()),
-            Err(engine_error) => {
-                eprintln!("Turn processing failed: {}", engine_error);
-                Ok(())
-            }
+        if self.client.is_some() {
+            self.update_from_server();
+            return Ok(());
+        }
+
+        if self.session.state() != GameState::Running {
+            return Ok(());
+        }
+
+        self.frames += 1;
+        let human_moved = self.inputs.iter().any(|(_, input)| input.is_pending());
+        let bots_move = !self.has_keyboard && self.frames >= BOT_TURN_FRAMES;
+        if !human_moved && !bots_move {
+            return Ok(());
+        }
+        self.frames = 0;
+
+        let result = self.session.play_turn();
+        self.track_puzzle();
+        match result {
+            Ok(errors) => errors
+                .iter()
+                .for_each(|error| eprintln!("Action refused: {}", error)),
+            Err(engine_error) => eprintln!("Turn processing failed: {}", engine_error),
         }
+        Ok(())
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
@@ -132,31 +660,52 @@ impl event::EventHandler for MainState {
         let dest_point = graphics::Point2::new(10.0, 10.0);
         graphics::draw(ctx, &self.text, dest_point, 0.0)?;
 
-        for entity in self.world.entities.values() {
+        let state = self.game_over.unwrap_or_else(|| self.session.state());
+        let status = match (&self.campaign, state) {
+            (Some(campaign), _) => Some(campaign.status()),
+            (None, GameState::Won(player)) => Some(format!("Player {} wins!", player)),
+            (None, GameState::Draw) => Some("It's a draw!".to_string()),
+            (None, GameState::Running) => None,
+        };
+        if let Some(status) = status {
+            let status = graphics::Text::new(ctx, &status, &self.small_font)?;
+            graphics::draw(ctx, &status, graphics::Point2::new(10.0, 65.0), 0.0)?;
+        }
+
+        for entity in self.view().entities() {
             self.draw_entity(ctx, &entity)?;
         }
+        self.draw_fog(ctx)?;
+
+        for entity_id in self.human_players() {
+            self.draw_legal_moves(ctx, entity_id)?;
+        }
 
         graphics::set_color(ctx, graphics::WHITE)?;
 
-        // Because the border is one coordinate ahead
-        let right_edge = self.world.right_edge.translate(1.0, 1.0);
-        let board = graphics::MeshBuilder::new()
-            .line(
-                &[
-                    Point2::new(START_X, START_Y),
-                    Point2::new(START_X + right_edge.x * ENTITY_SIZE, START_Y),
-                    Point2::new(
-                        START_X + right_edge.x * ENTITY_SIZE,
-                        START_Y + right_edge.y * ENTITY_SIZE,
-                    ),
-                    Point2::new(START_X, START_Y + right_edge.y * ENTITY_SIZE),
-                    Point2::new(START_X, START_Y),
-                ],
-                4.0,
-            )
-            .build(ctx)?;
+        if self.is_hex() {
+            self.draw_hex_grid(ctx)?;
+        } else {
+            // Because the border is one coordinate ahead
+            let right_edge = self.session.world.right_edge.translate(1.0, 1.0);
+            let board = graphics::MeshBuilder::new()
+                .line(
+                    &[
+                        Point2::new(START_X, START_Y),
+                        Point2::new(START_X + right_edge.x * ENTITY_SIZE, START_Y),
+                        Point2::new(
+                            START_X + right_edge.x * ENTITY_SIZE,
+                            START_Y + right_edge.y * ENTITY_SIZE,
+                        ),
+                        Point2::new(START_X, START_Y + right_edge.y * ENTITY_SIZE),
+                        Point2::new(START_X, START_Y),
+                    ],
+                    4.0,
+                )
+                .build(ctx)?;
 
-        graphics::draw(ctx, &board, graphics::Point2::new(0.0, 0.0), 0.0)?;
+            graphics::draw(ctx, &board, graphics::Point2::new(0.0, 0.0), 0.0)?;
+        }
 
         graphics::present(ctx);
 
@@ -164,44 +713,76 @@ impl event::EventHandler for MainState {
     }
 
     fn key_up_event(&mut self, _ctx: &mut Context, keycode: Keycode, _keymod: Mod, _repeat: bool) {
-        match keycode {
-            Keycode::Up => {
-                let action = engine::actions::movement::up(self.first_player_id);
-                self.world.register_action(action);
+        let level = self.campaign.as_ref().and_then(|campaign| match keycode {
+            Keycode::R => Some(campaign.current),
+            Keycode::N
+                if campaign.puzzle.is_solved() && campaign.current + 1 < campaign.levels.len() =>
+            {
+                Some(campaign.current + 1)
             }
-            Keycode::Left => {
-                let action = engine::actions::movement::left(self.first_player_id);
-                self.world.register_action(action);
-            }
-            Keycode::Right => {
-                let action = engine::actions::movement::right(self.first_player_id);
-                self.world.register_action(action);
-            }
-            Keycode::Down => {
-                let action = engine::actions::movement::down(self.first_player_id);
-                self.world.register_action(action);
-            }
-            Keycode::W => {
-                let action = engine::actions::movement::up(self.second_player_id);
-                self.world.register_action(action);
-            }
-            Keycode::A => {
-                let action = engine::actions::movement::left(self.second_player_id);
-                self.world.register_action(action);
+            _ => None,
+        });
+        if let Some(index) = level {
+            self.start_level(index);
+            return;
+        }
+
+        if self.client.is_some() {
+            if self.remote_bot.is_some() {
+                return;
             }
-            Keycode::D => {
-                let action = engine::actions::movement::right(self.second_player_id);
-                self.world.register_action(action);
+
+            let dir = KeyScheme::ALL
+                .iter()
+                .find_map(|scheme| scheme.direction(keycode));
+            if let Some(dir) = dir {
+                self.submit(move |_, id| Some(movement::towards(id, dir)))
+            } else if KeyScheme::ALL.iter().any(|scheme| scheme.shoots(keycode)) {
+                self.submit(closest_shot)
             }
-            Keycode::S => {
-                let action = engine::actions::movement::down(self.second_player_id);
-                self.world.register_action(action);
+            return;
+        }
+
+        for (scheme, input) in &self.inputs {
+            if let Some(dir) = scheme.direction(keycode) {
+                input.press(dir);
+            } else if scheme.shoots(keycode) {
+                input.shoot();
             }
-            _ => (),
         }
     }
 }
 
+/// Arrows, plus the numpad for diagonals.
+fn arrow_key(keycode: Keycode) -> Option<Direction> {
+    match keycode {
+        Keycode::Up | Keycode::Kp8 => Some(direction::UP),
+        Keycode::Left | Keycode::Kp4 => Some(direction::LEFT),
+        Keycode::Right | Keycode::Kp6 => Some(direction::RIGHT),
+        Keycode::Down | Keycode::Kp2 => Some(direction::DOWN),
+        Keycode::Kp7 => Some(direction::UP_LEFT),
+        Keycode::Kp9 => Some(direction::UP_RIGHT),
+        Keycode::Kp1 => Some(direction::DOWN_LEFT),
+        Keycode::Kp3 => Some(direction::DOWN_RIGHT),
+        _ => None,
+    }
+}
+
+/// WASD, plus QEZC for diagonals.
+fn wasd_key(keycode: Keycode) -> Option<Direction> {
+    match keycode {
+        Keycode::W => Some(direction::UP),
+        Keycode::A => Some(direction::LEFT),
+        Keycode::D => Some(direction::RIGHT),
+        Keycode::S => Some(direction::DOWN),
+        Keycode::Q => Some(direction::UP_LEFT),
+        Keycode::E => Some(direction::UP_RIGHT),
+        Keycode::Z => Some(direction::DOWN_LEFT),
+        Keycode::C => Some(direction::DOWN_RIGHT),
+        _ => None,
+    }
+}
+
 // Now our main function, which does three things:
 //
 // * First, create a new `ggez::conf::Conf`
@@ -210,7 +791,116 @@ impl event::EventHandler for MainState {
 // * Second, create a `ggez::game::Game` object which will
 // do the work of creating our MainState and running our game.
 // * Then, just call `game.run()` which runs the `Game` mainloop.
+//
+// Passing `--connect <address>` plays against a remote `server` instead
+// of sharing the keyboard, and `--player1` to `--player8` pick who controls
+// each player: `arrows` (arrows and Return to shoot), `wasd` (WASD and
+// Space), `keyboard` for the first of them still free, `random`, `greedy` or
+// `mcts`. Players default to the keyboard, those left without keys waiting.
+// A connected player given a bot lets it play on the server.
+// `--map <file>` plays on the map in the file, with up to 8 players.
+// `--eight-way` adds diagonal moves on the numpad and QEZC, and
+// `--corner-cutting` lets them squeeze past mountains. `--hex` plays on
+// hexes instead, moving with the arrows or WASD plus the numpad 9/1 or E/Z.
+// `--level <file>` plays the puzzle campaign in the file with the arrows, R
+// restarting the level and N moving on once it's solved. `--mode race` plays
+// for gold without shooting, `--mode deathmatch` to the last one standing.
+// `--rules <file>` reads the rules from a file instead, as written in
+// `rules.txt`. `--actors <file>` reads what maps are made of from a file, as
+// written in `engine/actors.txt`, actors naming a bot being played by it.
 pub fn main() {
+    let args: Vec<String> = env::args().collect();
+    let option = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .and_then(|index| args.get(index + 1))
+            .cloned()
+    };
+    let players: Vec<String> = (1..=player::MAX)
+        .map(|n| option(&format!("--player{}", n)).unwrap_or_else(|| "keyboard".to_string()))
+        .collect();
+    let map = option("--map").map_or_else(
+        || DEFAULT_MAP.join("\n"),
+        |path| match fs::read_to_string(&path) {
+            Ok(raw) => raw.trim_end_matches('\n').to_string(),
+            Err(error) => {
+                eprintln!("Can't read map {}: {}", path, error);
+                std::process::exit(1);
+            }
+        },
+    );
+    let prefabs = option("--actors").map_or_else(
+        || Prefabs::builtin().clone(),
+        |path| match fs::read_to_string(&path).map(|raw| prefabs::load(&raw)) {
+            Ok(Ok(prefabs)) => prefabs,
+            Ok(Err(error)) => {
+                eprintln!("Invalid actors {}: {}", path, error);
+                std::process::exit(1);
+            }
+            Err(error) => {
+                eprintln!("Can't read actors {}: {}", path, error);
+                std::process::exit(1);
+            }
+        },
+    );
+    let rules = if let Some(path) = option("--rules") {
+        match fs::read_to_string(&path).map(|raw| rules::load(&raw)) {
+            Ok(Ok(rules)) => Some(rules),
+            Ok(Err(error)) => {
+                eprintln!("Invalid rules {}: {}", path, error);
+                std::process::exit(1);
+            }
+            Err(error) => {
+                eprintln!("Can't read rules {}: {}", path, error);
+                std::process::exit(1);
+            }
+        }
+    } else if args.iter().any(|arg| arg == "--hex") {
+        Some(Rules::hex())
+    } else if args.iter().any(|arg| arg == "--eight-way") {
+        Some(Rules::eight_way(
+            args.iter().any(|arg| arg == "--corner-cutting"),
+        ))
+    } else {
+        None
+    };
+
+    // Maps keep their own rules unless some were asked for
+    let mut world = basic::load_with(&map, &prefabs);
+    if let Some(rules) = rules {
+        world.set_rules(rules);
+    }
+
+    let mode = option("--mode").unwrap_or_else(|| "classic".to_string());
+    let mode = game::mode::by_name(&mode).unwrap_or_else(|| {
+        eprintln!("Unknown game mode `{}`", mode);
+        std::process::exit(1);
+    });
+
+    let levels = option("--level").map(|path| match fs::read_to_string(&path) {
+        Ok(raw) => level::load_campaign(&raw),
+        Err(error) => {
+            eprintln!("Can't read levels {}: {}", path, error);
+            std::process::exit(1);
+        }
+    });
+
+    let client = if args.iter().any(|arg| arg == "--connect") {
+        let addr = option("--connect").unwrap_or_else(|| "127.0.0.1:7878".to_string());
+        match Client::connect(&addr) {
+            Ok(client) => {
+                println!("Connected to {} as player {}", addr, client.player);
+                Some(client)
+            }
+            Err(error) => {
+                eprintln!("Can't connect to {}: {}", addr, error);
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
     let c = conf::Conf::new();
     let ctx = &mut Context::load_from_conf("helloworld", "ggez", c).unwrap();
 
@@ -222,9 +912,9 @@ pub fn main() {
         ctx.filesystem.mount(&path, true);
     }
 
-    let state = &mut MainState::new(ctx);
+    let state = &mut MainState::new(ctx, client, &players, mode, world, prefabs, levels);
     if let Err(e) = event::run(ctx, state) {
-        println!("Error encountered: {}", e);
+        eprintln!("Error encountered: {}", e);
     } else {
         println!("Game exited cleanly.");
     }